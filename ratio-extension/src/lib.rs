@@ -1,7 +1,13 @@
 mod ratio_ext;
+#[cfg(feature = "nalgebra")]
+mod vector;
 
 use num_bigint::BigInt;
-pub use ratio_ext::RatioExt;
+pub use ratio_ext::{
+    lcm_denominators, ArithmeticError, ParseRatioExtError, RatioExt, RatioExtIterator,
+};
+#[cfg(feature = "nalgebra")]
+pub use vector::*;
 
 pub type BigRationalExt = RatioExt<BigInt>;
 pub type Rational32Ext = RatioExt<i32>;
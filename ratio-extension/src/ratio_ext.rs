@@ -1,19 +1,25 @@
 use std::{
     cmp::Ordering,
+    error::Error,
     fmt,
     hint::unreachable_unchecked,
     iter::{Product, Sum},
     mem,
-    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign},
+    str::FromStr,
 };
 
 use derive_more::IsVariant;
 use num_bigint::BigInt;
 use num_integer::Integer;
-use num_rational::Ratio;
-use num_traits::{float::FloatCore, FromPrimitive, One, Zero};
+use num_rational::{ParseRatioError, Ratio};
+use num_traits::{
+    float::FloatCore, CheckedAdd, CheckedMul, FromPrimitive, Num, One, ToPrimitive, Zero,
+};
 use serde::{Deserialize, Serialize};
 
+use crate::BigRationalExt;
+
 #[derive(Debug, Clone, IsVariant, Serialize, Deserialize)]
 pub enum RatioExt<T: Clone + Integer> {
     Inf,
@@ -39,6 +45,19 @@ where
         Self::Finite(Ratio::<T>::from_integer(integer))
     }
 
+    /// Same as `RatioExt::from(ratio)`, but without the ambiguity of the generic [`From`]
+    pub const fn from_ratio(ratio: Ratio<T>) -> RatioExt<T> {
+        Self::Finite(ratio)
+    }
+
+    /// Unlike [`RatioExt::finite`], returns the non-finite value back untouched
+    pub fn into_finite(self) -> Result<Ratio<T>, Self> {
+        match self {
+            Self::Finite(value) => Ok(value),
+            value => Err(value),
+        }
+    }
+
     pub fn finite(self) -> Option<Ratio<T>> {
         if let Self::Finite(value) = self {
             return Some(value);
@@ -55,6 +74,15 @@ where
         unreachable_unchecked()
     }
 
+    /// The finite value, or the `default` for [`RatioExt::Inf`], [`RatioExt::MinusInf`] and [`RatioExt::Nan`],
+    /// which have no numeric representation in the exported data
+    pub fn finite_or(&self, default: Ratio<T>) -> Ratio<T> {
+        match self {
+            Self::Finite(value) => value.clone(),
+            _ => default,
+        }
+    }
+
     pub const fn finite_as_ref(&self) -> Option<&Ratio<T>> {
         if let Self::Finite(value) = self {
             return Some(value);
@@ -89,6 +117,75 @@ impl RatioExt<BigInt> {
             RatioExt::Finite,
         )
     }
+
+    /// Parses a decimal number like `"12.5"`, `"-0.25"` or `"1e3"` exactly, unlike [`RatioExt::from_float`],
+    /// which keeps the binary round-off of the float (`0.1` is not `1/10`).
+    /// `"Inf"`, `"-Inf"` and `"NaN"` are recognized as they are displayed.
+    /// The exponent is limited to `±4096`, so that `"1e999999999"` is rejected instead of computing the power
+    pub fn from_decimal_str(s: &str) -> Result<Self, String> {
+        match s {
+            "Inf" | "+Inf" => return Ok(RatioExt::Inf),
+            "-Inf" => return Ok(RatioExt::MinusInf),
+            "NaN" => return Ok(RatioExt::Nan),
+            _ => {}
+        }
+        let invalid = || format!("Invalid decimal number: {s:?}");
+
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (digits, exponent) = match digits.split_once(['e', 'E']) {
+            Some((digits, exponent)) => (
+                digits,
+                exponent
+                    .parse::<i32>()
+                    .ok()
+                    .filter(|exponent| exponent.unsigned_abs() <= MAX_DECIMAL_EXPONENT)
+                    .ok_or_else(invalid)?,
+            ),
+            None => (digits, 0),
+        };
+        let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+        if (int_part.is_empty() && frac_part.is_empty())
+            || !int_part
+                .chars()
+                .chain(frac_part.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let numer: BigInt = format!("{int_part}{frac_part}").parse().unwrap();
+        let scale = i64::from(exponent) - frac_part.len() as i64;
+        let power = num_traits::pow(BigInt::from(10), scale.unsigned_abs() as usize);
+        let ratio = if scale < 0 {
+            Ratio::new(numer, power)
+        } else {
+            Ratio::from_integer(numer * power)
+        };
+        Ok(RatioExt::Finite(if negative { -ratio } else { ratio }))
+    }
+}
+
+/// Greatest absolute value of the exponent, accepted by [`RatioExt::from_decimal_str`]
+const MAX_DECIMAL_EXPONENT: u32 = 4096;
+
+/// Least common multiple of the denominators of the finite `values`, that turns all of them into integers.
+/// The infinities and [`RatioExt::Nan`] are skipped, so it is [`None`] only without finite values
+pub fn lcm_denominators(values: &[BigRationalExt]) -> Option<BigInt> {
+    values
+        .iter()
+        .filter_map(|value| match value {
+            RatioExt::Finite(ratio) => Some(ratio.denom()),
+            _ => None,
+        })
+        .fold(None, |lcm: Option<BigInt>, denom| {
+            Some(match lcm {
+                Some(lcm) => lcm.lcm(denom),
+                None => denom.clone(),
+            })
+        })
 }
 
 impl FromPrimitive for RatioExt<BigInt> {
@@ -155,6 +252,36 @@ where
     }
 }
 
+/// Compares with the integer as with the [`RatioExt::Finite`] one, so `x == 5` needs no [`RatioExt::from_integer`].
+/// The infinities are never equal to an integer, [`RatioExt::Nan`] is not equal to anything
+impl<T> PartialEq<T> for RatioExt<T>
+where
+    T: Clone + Integer,
+{
+    fn eq(&self, other: &T) -> bool {
+        match self {
+            RatioExt::Finite(ratio) => ratio.is_integer() && ratio.numer() == other,
+            _ => false,
+        }
+    }
+}
+
+/// Compares with the integer as with the [`RatioExt::Finite`] one: [`RatioExt::Inf`] is greater
+/// and [`RatioExt::MinusInf`] is less, than any integer, while [`RatioExt::Nan`] is not comparable
+impl<T> PartialOrd<T> for RatioExt<T>
+where
+    T: Clone + Integer,
+{
+    fn partial_cmp(&self, other: &T) -> Option<Ordering> {
+        match self {
+            RatioExt::Nan => None,
+            RatioExt::Inf => Some(Ordering::Greater),
+            RatioExt::MinusInf => Some(Ordering::Less),
+            RatioExt::Finite(ratio) => ratio.partial_cmp(&Ratio::from_integer(other.clone())),
+        }
+    }
+}
+
 impl<T> RatioExt<T>
 where
     T: Integer + Clone,
@@ -170,8 +297,101 @@ where
             (_, RatioExt::MinusInf) => Ordering::Greater,
         }
     }
+
+    /// Whether the value is greater than zero. [`RatioExt::Inf`] is positive, [`RatioExt::Nan`] is not
+    pub fn is_positive(&self) -> bool {
+        match self {
+            RatioExt::Inf => true,
+            RatioExt::Finite(ratio) => *ratio > Ratio::zero(),
+            RatioExt::MinusInf | RatioExt::Nan => false,
+        }
+    }
+
+    /// Whether the value is less than zero. [`RatioExt::MinusInf`] is negative, [`RatioExt::Nan`] is not
+    pub fn is_negative(&self) -> bool {
+        match self {
+            RatioExt::MinusInf => true,
+            RatioExt::Finite(ratio) => *ratio < Ratio::zero(),
+            RatioExt::Inf | RatioExt::Nan => false,
+        }
+    }
+
+    /// Whether the value is positive or zero. Unlike `!is_negative()`, is `false` for [`RatioExt::Nan`]
+    pub fn is_non_negative(&self) -> bool {
+        !self.is_nan() && !self.is_negative()
+    }
+
+    /// Integer power by squaring, so a finite base takes `O(log exp)` multiplications.
+    /// A negative `exp` gives the reciprocal, and `0.powi(-1)` is [`RatioExt::Inf`], as `1 / 0` is.
+    ///
+    /// Like [`f64::powi`], anything to the power of zero is one, [`RatioExt::Nan`] included.
+    /// An infinity to a negative power is zero, [`RatioExt::MinusInf`] to an odd power stays negative
+    pub fn powi(&self, exp: i32) -> Self {
+        if exp == 0 {
+            return Self::one();
+        }
+        match self {
+            RatioExt::Nan => RatioExt::Nan,
+            RatioExt::Inf | RatioExt::MinusInf if exp < 0 => Self::zero(),
+            RatioExt::MinusInf if exp % 2 != 0 => RatioExt::MinusInf,
+            RatioExt::Inf | RatioExt::MinusInf => RatioExt::Inf,
+            RatioExt::Finite(_) => {
+                let mut base = self.clone();
+                let mut n = exp.unsigned_abs();
+                let mut power = Self::one();
+                while n > 0 {
+                    if n & 1 == 1 {
+                        power = &power * &base;
+                    }
+                    n >>= 1;
+                    if n > 0 {
+                        base = &base * &base;
+                    }
+                }
+                if exp < 0 {
+                    &Self::one() / &power
+                } else {
+                    power
+                }
+            }
+        }
+    }
+
+    /// Structural equality: unlike `==`, treats [`RatioExt::Nan`] as equal to itself.
+    ///
+    /// Meant for assertions and deduplication, the operator stays IEEE-compliant
+    pub fn bitwise_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (RatioExt::Finite(lhs), RatioExt::Finite(rhs)) => lhs == rhs,
+            (RatioExt::Inf, RatioExt::Inf)
+            | (RatioExt::MinusInf, RatioExt::MinusInf)
+            | (RatioExt::Nan, RatioExt::Nan) => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the distance between finite `self` and `other` is at most `tol`.
+    ///
+    /// Equal infinities are approximately equal, while the mixed ones are not.
+    /// [`RatioExt::Nan`] is never approximately equal to anything, itself included
+    pub fn approx_eq(&self, other: &Self, tol: &Self) -> bool {
+        match (self, other) {
+            (RatioExt::Finite(_), RatioExt::Finite(_)) => {
+                // The absolute value of the difference, without requiring the signed integers
+                let distance = match self.total_cmp(other) {
+                    Ordering::Less => other - self,
+                    _ => self - other,
+                };
+                distance.total_cmp(tol).is_le() && !tol.is_nan()
+            }
+            (RatioExt::Inf, RatioExt::Inf) | (RatioExt::MinusInf, RatioExt::MinusInf) => true,
+            _ => false,
+        }
+    }
 }
 
+/// Anything, that converts into a [`Ratio`], is a finite value.
+/// In particular, `BigRationalExt::from(big_rational)` is [`RatioExt::Finite`]
 impl<T, U> From<T> for RatioExt<U>
 where
     Ratio<U>: From<T>,
@@ -182,6 +402,9 @@ where
     }
 }
 
+/// Only [`RatioExt::Finite`] converts into a [`Ratio`],
+/// `BigRational::try_from` of [`RatioExt::Inf`], [`RatioExt::MinusInf`] or [`RatioExt::Nan`]
+/// returns the value back as the error
 impl<T> TryFrom<RatioExt<T>> for Ratio<T>
 where
     T: Clone + Integer,
@@ -189,10 +412,7 @@ where
     type Error = RatioExt<T>;
 
     fn try_from(value: RatioExt<T>) -> Result<Self, Self::Error> {
-        match value {
-            RatioExt::Finite(ratio) => Ok(ratio),
-            value => Err(value),
-        }
+        value.into_finite()
     }
 }
 
@@ -214,6 +434,61 @@ where
     }
 }
 
+/// Indeterminate form, that [`RatioExtIterator::try_sum`] and [`RatioExtIterator::try_product`] fail on,
+/// instead of producing [`RatioExt::Nan`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithmeticError {
+    /// `Inf + MinusInf`
+    InfMinusInf,
+    /// `0 * Inf` or `0 * MinusInf`
+    ZeroTimesInf,
+    /// One of the values is [`RatioExt::Nan`] already
+    Nan,
+}
+
+impl fmt::Display for ArithmeticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InfMinusInf => write!(f, "Indeterminate form Inf + -Inf"),
+            Self::ZeroTimesInf => write!(f, "Indeterminate form 0 * Inf"),
+            Self::Nan => write!(f, "NaN operand"),
+        }
+    }
+}
+
+impl Error for ArithmeticError {}
+
+/// Folds of the iterators of [`RatioExt`], that fail fast on the indeterminate forms.
+///
+/// Unlike the [`Sum`] and the [`Product`], which carry the [`RatioExt::Nan`] through,
+/// and take `0 * Inf` for zero, so they suit the folds, where an indeterminate form is a bug
+pub trait RatioExtIterator<T: Clone + Integer>: Iterator<Item = RatioExt<T>> + Sized {
+    fn try_sum(mut self) -> Result<RatioExt<T>, ArithmeticError> {
+        self.try_fold(RatioExt::zero(), |sum, el| match (&sum, &el) {
+            (RatioExt::Nan, _) | (_, RatioExt::Nan) => Err(ArithmeticError::Nan),
+            (RatioExt::Inf, RatioExt::MinusInf) | (RatioExt::MinusInf, RatioExt::Inf) => {
+                Err(ArithmeticError::InfMinusInf)
+            }
+            _ => Ok(sum + el),
+        })
+    }
+
+    fn try_product(mut self) -> Result<RatioExt<T>, ArithmeticError> {
+        let is_infinite = |value: &RatioExt<T>| value.is_inf() || value.is_minus_inf();
+        self.try_fold(RatioExt::one(), |product, el| {
+            if product.is_nan() || el.is_nan() {
+                return Err(ArithmeticError::Nan);
+            }
+            if product.is_zero() && is_infinite(&el) || is_infinite(&product) && el.is_zero() {
+                return Err(ArithmeticError::ZeroTimesInf);
+            }
+            Ok(product * el)
+        })
+    }
+}
+
+impl<T: Clone + Integer, I: Iterator<Item = RatioExt<T>>> RatioExtIterator<T> for I {}
+
 impl<T> Zero for RatioExt<T>
 where
     T: Clone + Integer,
@@ -278,6 +553,9 @@ where
             },
             RatioExt::Finite(lhs) => match rhs {
                 RatioExt::Inf => RatioExt::Inf,
+                // Fast paths, skipping the `Ratio` arithmetic
+                RatioExt::Finite(rhs) if lhs.is_zero() => RatioExt::Finite(rhs.clone()),
+                RatioExt::Finite(rhs) if rhs.is_zero() => RatioExt::Finite(lhs.clone()),
                 RatioExt::Finite(rhs) => RatioExt::Finite(lhs + rhs),
                 RatioExt::MinusInf => RatioExt::MinusInf,
                 _ => unsafe { unreachable_unchecked() },
@@ -336,6 +614,31 @@ where
     }
 }
 
+impl<T> AddAssign<Ratio<T>> for RatioExt<T>
+where
+    T: Clone + Integer,
+{
+    fn add_assign(&mut self, rhs: Ratio<T>) {
+        if let Self::Finite(lhs) = self {
+            *lhs = mem::take(lhs) + rhs;
+        }
+    }
+}
+
+/// Returns `None`, if the numerator or the denominator overflows `T`.
+/// Infinities and `NaN` can't overflow, so they follow the rules of [`Add`]
+impl<T> CheckedAdd for RatioExt<T>
+where
+    T: Clone + Integer + CheckedAdd + CheckedMul,
+{
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        match (self, rhs) {
+            (Self::Finite(lhs), Self::Finite(rhs)) => lhs.checked_add(rhs).map(Self::Finite),
+            _ => Some(self + rhs),
+        }
+    }
+}
+
 impl<T> Sub for RatioExt<T>
 where
     T: Clone + Integer,
@@ -424,6 +727,17 @@ where
     }
 }
 
+impl<T> SubAssign<Ratio<T>> for RatioExt<T>
+where
+    T: Clone + Integer,
+{
+    fn sub_assign(&mut self, rhs: Ratio<T>) {
+        if let Self::Finite(lhs) = self {
+            *lhs = mem::take(lhs) - rhs;
+        }
+    }
+}
+
 impl<T> Mul for RatioExt<T>
 where
     T: Clone + Integer,
@@ -447,34 +761,28 @@ where
         }
         match self {
             RatioExt::Inf => match rhs {
-                RatioExt::Inf => RatioExt::Inf,
-                RatioExt::Finite(rhs) => match rhs {
-                    _ if *rhs < Ratio::zero() => RatioExt::MinusInf,
-                    _ if rhs.is_zero() => RatioExt::zero(),
-                    _ => RatioExt::Inf,
-                },
-                RatioExt::MinusInf => RatioExt::MinusInf,
-                _ => unsafe { unreachable_unchecked() },
+                _ if rhs.is_positive() => RatioExt::Inf,
+                _ if rhs.is_negative() => RatioExt::MinusInf,
+                _ => RatioExt::zero(),
             },
             RatioExt::Finite(lhs) => match rhs {
-                RatioExt::Inf => match lhs {
-                    _ if *lhs < Ratio::zero() => RatioExt::MinusInf,
-                    _ if lhs.is_zero() => RatioExt::zero(),
-                    _ => RatioExt::Inf,
+                RatioExt::Inf => match self {
+                    _ if self.is_positive() => RatioExt::Inf,
+                    _ if self.is_negative() => RatioExt::MinusInf,
+                    _ => RatioExt::zero(),
                 },
+                // Fast paths, skipping the `Ratio` arithmetic
+                RatioExt::Finite(rhs) if lhs.is_zero() || rhs.is_zero() => RatioExt::zero(),
+                RatioExt::Finite(rhs) if lhs.is_one() => RatioExt::Finite(rhs.clone()),
+                RatioExt::Finite(rhs) if rhs.is_one() => RatioExt::Finite(lhs.clone()),
                 RatioExt::Finite(rhs) => RatioExt::Finite(lhs * rhs),
                 RatioExt::MinusInf => RatioExt::MinusInf,
                 _ => unsafe { unreachable_unchecked() },
             },
             RatioExt::MinusInf => match rhs {
-                RatioExt::Inf => RatioExt::MinusInf,
-                RatioExt::MinusInf => RatioExt::Inf,
-                RatioExt::Finite(rhs) => match rhs {
-                    _ if *rhs < Ratio::zero() => RatioExt::Inf,
-                    _ if rhs.is_zero() => RatioExt::zero(),
-                    _ => RatioExt::MinusInf,
-                },
-                _ => unsafe { unreachable_unchecked() },
+                _ if rhs.is_positive() => RatioExt::MinusInf,
+                _ if rhs.is_negative() => RatioExt::Inf,
+                _ => RatioExt::zero(),
             },
             _ => unsafe { unreachable_unchecked() },
         }
@@ -501,14 +809,9 @@ where
             }
             match self {
                 Self::Inf => match rhs {
-                    Self::Inf => Self::Inf,
-                    Self::Finite(rhs) => match rhs {
-                        _ if *rhs < Ratio::zero() => Self::MinusInf,
-                        _ if rhs.is_zero() => Self::zero(),
-                        _ => Self::Inf,
-                    },
-                    Self::MinusInf => Self::MinusInf,
-                    _ => unsafe { unreachable_unchecked() },
+                    _ if rhs.is_positive() => Self::Inf,
+                    _ if rhs.is_negative() => Self::MinusInf,
+                    _ => Self::zero(),
                 },
                 Self::Finite(lhs) => match rhs {
                     Self::Inf => match lhs {
@@ -521,14 +824,9 @@ where
                     _ => unsafe { unreachable_unchecked() },
                 },
                 Self::MinusInf => match rhs {
-                    Self::Inf => Self::MinusInf,
-                    Self::MinusInf => Self::Inf,
-                    Self::Finite(rhs) => match rhs {
-                        _ if *rhs < Ratio::zero() => Self::Inf,
-                        _ if rhs.is_zero() => Self::zero(),
-                        _ => Self::MinusInf,
-                    },
-                    _ => unsafe { unreachable_unchecked() },
+                    _ if rhs.is_positive() => Self::MinusInf,
+                    _ if rhs.is_negative() => Self::Inf,
+                    _ => Self::zero(),
                 },
                 _ => unsafe { unreachable_unchecked() },
             }
@@ -536,6 +834,42 @@ where
     }
 }
 
+impl<T> MulAssign<Ratio<T>> for RatioExt<T>
+where
+    T: Clone + Integer,
+{
+    fn mul_assign(&mut self, rhs: Ratio<T>) {
+        *self = match self {
+            Self::Nan => Self::Nan,
+            Self::Inf => match rhs {
+                _ if rhs < Ratio::zero() => Self::MinusInf,
+                _ if rhs.is_zero() => Self::zero(),
+                _ => Self::Inf,
+            },
+            Self::Finite(lhs) => Self::Finite(mem::take(lhs) * rhs),
+            Self::MinusInf => match rhs {
+                _ if rhs < Ratio::zero() => Self::Inf,
+                _ if rhs.is_zero() => Self::zero(),
+                _ => Self::MinusInf,
+            },
+        }
+    }
+}
+
+/// Returns `None`, if the numerator or the denominator overflows `T`.
+/// Infinities and `NaN` can't overflow, so they follow the rules of [`Mul`]
+impl<T> CheckedMul for RatioExt<T>
+where
+    T: Clone + Integer + CheckedMul,
+{
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        match (self, rhs) {
+            (Self::Finite(lhs), Self::Finite(rhs)) => lhs.checked_mul(rhs).map(Self::Finite),
+            _ => Some(self * rhs),
+        }
+    }
+}
+
 impl<T> Div for RatioExt<T>
 where
     T: Clone + Integer,
@@ -559,19 +893,16 @@ where
         }
         match self {
             RatioExt::Inf => match rhs {
-                RatioExt::Finite(rhs) => match rhs {
-                    _ if *rhs < Ratio::zero() => RatioExt::MinusInf,
-                    _ => RatioExt::Inf,
-                },
                 RatioExt::Inf | RatioExt::MinusInf => RatioExt::Nan,
-                _ => unsafe { unreachable_unchecked() },
+                _ if rhs.is_negative() => RatioExt::MinusInf,
+                _ => RatioExt::Inf,
             },
             RatioExt::Finite(lhs) => match rhs {
                 RatioExt::Finite(rhs) => match rhs {
-                    _ if rhs.is_zero() => match lhs {
-                        _ if lhs.is_zero() => RatioExt::Nan,
-                        _ if *lhs < Ratio::zero() => RatioExt::MinusInf,
-                        _ => RatioExt::Inf,
+                    _ if rhs.is_zero() => match self {
+                        _ if self.is_positive() => RatioExt::Inf,
+                        _ if self.is_negative() => RatioExt::MinusInf,
+                        _ => RatioExt::Nan,
                     },
                     _ => RatioExt::Finite(lhs / rhs),
                 },
@@ -579,12 +910,9 @@ where
                 _ => unsafe { unreachable_unchecked() },
             },
             RatioExt::MinusInf => match rhs {
-                RatioExt::Finite(rhs) => match rhs {
-                    _ if *rhs < Ratio::zero() => RatioExt::Inf,
-                    _ => RatioExt::MinusInf,
-                },
                 RatioExt::Inf | RatioExt::MinusInf => RatioExt::Nan,
-                _ => unsafe { unreachable_unchecked() },
+                _ if rhs.is_negative() => RatioExt::Inf,
+                _ => RatioExt::MinusInf,
             },
             _ => unsafe { unreachable_unchecked() },
         }
@@ -611,12 +939,9 @@ where
             }
             match self {
                 Self::Inf => match rhs {
-                    Self::Finite(rhs) => match rhs {
-                        _ if *rhs < Ratio::zero() => Self::MinusInf,
-                        _ => Self::Inf,
-                    },
                     Self::Inf | Self::MinusInf => Self::Nan,
-                    _ => unsafe { unreachable_unchecked() },
+                    _ if rhs.is_negative() => Self::MinusInf,
+                    _ => Self::Inf,
                 },
                 Self::Finite(lhs) => match rhs {
                     Self::Finite(rhs) => match rhs {
@@ -631,12 +956,9 @@ where
                     _ => unsafe { unreachable_unchecked() },
                 },
                 Self::MinusInf => match rhs {
-                    Self::Finite(rhs) => match rhs {
-                        _ if *rhs < Ratio::zero() => Self::Inf,
-                        _ => Self::MinusInf,
-                    },
                     Self::Inf | Self::MinusInf => Self::Nan,
-                    _ => unsafe { unreachable_unchecked() },
+                    _ if rhs.is_negative() => Self::Inf,
+                    _ => Self::MinusInf,
                 },
                 _ => unsafe { unreachable_unchecked() },
             }
@@ -644,6 +966,33 @@ where
     }
 }
 
+impl<T> DivAssign<Ratio<T>> for RatioExt<T>
+where
+    T: Integer + Clone,
+{
+    fn div_assign(&mut self, rhs: Ratio<T>) {
+        *self = match self {
+            Self::Nan => Self::Nan,
+            Self::Inf => match rhs {
+                _ if rhs < Ratio::zero() => Self::MinusInf,
+                _ => Self::Inf,
+            },
+            Self::Finite(lhs) => match rhs {
+                _ if rhs.is_zero() => match lhs {
+                    _ if lhs.is_zero() => Self::Nan,
+                    _ if *lhs < Ratio::zero() => Self::MinusInf,
+                    _ => Self::Inf,
+                },
+                _ => Self::Finite(mem::take(lhs) / rhs),
+            },
+            Self::MinusInf => match rhs {
+                _ if rhs < Ratio::zero() => Self::Inf,
+                _ => Self::MinusInf,
+            },
+        }
+    }
+}
+
 impl<T> Neg for RatioExt<T>
 where
     T: Clone + Integer + Neg<Output = T>,
@@ -660,13 +1009,114 @@ where
     }
 }
 
+/// Same as [`Ratio`] for finite values, but infinite when dividing by zero, like the floats:
+/// `x % 0` and `±Inf % y` are [`RatioExt::Nan`], while `x % ±Inf` is `x`
+impl<T> Rem for RatioExt<T>
+where
+    T: Clone + Integer,
+{
+    type Output = RatioExt<T>;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        &self % &rhs
+    }
+}
+
+impl<T> Rem for &RatioExt<T>
+where
+    T: Clone + Integer,
+{
+    type Output = RatioExt<T>;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        match (self, rhs) {
+            (RatioExt::Finite(lhs), RatioExt::Finite(rhs)) if !rhs.is_zero() => {
+                RatioExt::Finite(lhs % rhs)
+            }
+            (RatioExt::Finite(lhs), RatioExt::Inf | RatioExt::MinusInf) => {
+                RatioExt::Finite(lhs.clone())
+            }
+            _ => RatioExt::Nan,
+        }
+    }
+}
+
+/// Error of parsing a [`RatioExt`] from a string
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseRatioExtError {
+    /// Only the decimal numbers are parsed
+    Radix(u32),
+    /// The string is neither `Inf`, `-Inf`, `NaN`, nor a [`Ratio`]
+    Ratio(ParseRatioError),
+}
+
+impl fmt::Display for ParseRatioExtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Radix(radix) => write!(f, "Unsupported radix {radix}, only 10 is supported"),
+            Self::Ratio(err) => write!(f, "Invalid ratio: {err}"),
+        }
+    }
+}
+
+impl Error for ParseRatioExtError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Radix(_) => None,
+            Self::Ratio(err) => Some(err),
+        }
+    }
+}
+
+/// Parses the fraction like `"3/4"`, the integer, or `"Inf"`, `"-Inf"` and `"NaN"` as they are displayed.
+/// Use [`RatioExt::from_decimal_str`] for the decimal fractions like `"0.75"`
+impl<T> FromStr for RatioExt<T>
+where
+    T: Clone + Integer + FromStr,
+{
+    type Err = ParseRatioExtError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Inf" | "+Inf" => Ok(RatioExt::Inf),
+            "-Inf" => Ok(RatioExt::MinusInf),
+            "NaN" => Ok(RatioExt::Nan),
+            _ => s
+                .parse()
+                .map(RatioExt::Finite)
+                .map_err(ParseRatioExtError::Ratio),
+        }
+    }
+}
+
+/// Only the radix 10 is supported, which delegates to [`FromStr`]
+impl<T> Num for RatioExt<T>
+where
+    T: Clone + Integer + FromStr,
+{
+    type FromStrRadixErr = ParseRatioExtError;
+
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        if radix != 10 {
+            return Err(ParseRatioExtError::Radix(radix));
+        }
+        s.parse()
+    }
+}
+
+/// Finite values are printed as an exact fraction, unless the precision is specified
+/// (`{:.3}`), in which case the decimal approximation is printed
 impl<T> fmt::Display for RatioExt<T>
 where
     T: fmt::Display + Integer + Clone,
+    Ratio<T>: ToPrimitive,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Finite(ratio) => write!(f, "{ratio}"),
+            Self::Finite(ratio) => match (f.precision(), ratio.to_f64()) {
+                (Some(precision), Some(value)) => write!(f, "{value:.precision$}"),
+                _ => write!(f, "{ratio}"),
+            },
             Self::Inf => write!(f, "Inf"),
             Self::MinusInf => write!(f, "-Inf"),
             Self::Nan => write!(f, "NaN"),
@@ -734,4 +1184,404 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn from_decimal_str_is_exact() {
+        assert_eq!(
+            RatioExt::from_decimal_str("0.1"),
+            Ok(RatioExt::Finite(Ratio::new(1.into(), 10.into())))
+        );
+        assert_ne!(
+            RatioExt::from_float(0.1),
+            RatioExt::from_decimal_str("0.1").unwrap()
+        );
+    }
+
+    #[test]
+    fn from_decimal_str() {
+        use crate::BigRationalExt;
+
+        let ratio =
+            |numer: i64, denom: i64| RatioExt::Finite(Ratio::new(numer.into(), denom.into()));
+
+        assert_eq!(BigRationalExt::from_decimal_str("42"), Ok(ratio(42, 1)));
+        assert_eq!(BigRationalExt::from_decimal_str("12.5"), Ok(ratio(25, 2)));
+        assert_eq!(BigRationalExt::from_decimal_str("-0.25"), Ok(ratio(-1, 4)));
+        assert_eq!(BigRationalExt::from_decimal_str("+.5"), Ok(ratio(1, 2)));
+        assert_eq!(BigRationalExt::from_decimal_str("1e3"), Ok(ratio(1000, 1)));
+        assert_eq!(BigRationalExt::from_decimal_str("2.5E-2"), Ok(ratio(1, 40)));
+        assert_eq!(
+            BigRationalExt::from_decimal_str("-1.25e1"),
+            Ok(ratio(-25, 2))
+        );
+        assert_eq!(BigRationalExt::from_decimal_str("Inf"), Ok(RatioExt::Inf));
+        assert_eq!(
+            BigRationalExt::from_decimal_str("-Inf"),
+            Ok(RatioExt::MinusInf)
+        );
+        assert!(BigRationalExt::from_decimal_str("NaN").unwrap().is_nan());
+
+        for invalid in [
+            "", "-", ".", "1.2.3", "1e", "e3", "1e3.5", "0x10", " 1", "inf",
+        ] {
+            assert_eq!(
+                BigRationalExt::from_decimal_str(invalid),
+                Err(format!("Invalid decimal number: {invalid:?}"))
+            );
+        }
+    }
+
+    #[test]
+    fn from_decimal_str_limits_the_exponent() {
+        use crate::BigRationalExt;
+
+        assert!(BigRationalExt::from_decimal_str("1e4096").is_ok());
+        assert!(BigRationalExt::from_decimal_str("1e-4096").is_ok());
+        for invalid in ["1e4097", "1e999999999", "-2.5E-999999999", "1e-2147483648"] {
+            assert_eq!(
+                BigRationalExt::from_decimal_str(invalid),
+                Err(format!("Invalid decimal number: {invalid:?}"))
+            );
+        }
+    }
+
+    #[test]
+    fn bitwise_eq() {
+        let nan = RatioExt::<i32>::Nan;
+        let inf = RatioExt::<i32>::Inf;
+        let minus_inf = RatioExt::<i32>::MinusInf;
+        let half = RatioExt::<i32>::from((1, 2));
+
+        assert!(nan != nan);
+        assert!(nan.bitwise_eq(&nan));
+
+        assert!(inf == inf);
+        assert!(inf.bitwise_eq(&inf));
+        assert!(minus_inf == minus_inf);
+        assert!(minus_inf.bitwise_eq(&minus_inf));
+
+        assert!(!inf.bitwise_eq(&minus_inf));
+        assert!(!nan.bitwise_eq(&inf));
+        assert!(half.bitwise_eq(&(2, 4).into()));
+        assert!(!half.bitwise_eq(&nan));
+    }
+
+    #[test]
+    fn add_mul_fast_paths_agree_with_full_arithmetic() {
+        let values =
+            [(0, 1), (1, 1), (-1, 1), (1, 3), (-7, 2)].map(|(n, d)| Ratio::<i32>::new(n, d));
+
+        for lhs in &values {
+            for rhs in &values {
+                let lhs_ext = RatioExt::Finite(*lhs);
+                let rhs_ext = RatioExt::Finite(*rhs);
+                assert_eq!(&lhs_ext + &rhs_ext, RatioExt::Finite(lhs + rhs));
+                assert_eq!(&lhs_ext * &rhs_ext, RatioExt::Finite(lhs * rhs));
+            }
+        }
+    }
+
+    #[test]
+    fn mul_by_zero_with_infinity_is_zero() {
+        let zero = RatioExt::<i32>::zero();
+
+        assert_eq!(&RatioExt::Inf * &zero, zero);
+        assert_eq!(&zero * &RatioExt::Inf, zero);
+        assert_eq!(&RatioExt::MinusInf * &zero, zero);
+        assert_eq!(&RatioExt::<i32>::one() * &RatioExt::Inf, RatioExt::Inf);
+        assert!((&zero * &RatioExt::Nan).is_nan());
+    }
+
+    #[test]
+    fn checked_ops_report_overflow() {
+        use crate::Rational64Ext;
+
+        let big = Rational64Ext::from((i64::MAX, 3));
+        let two = Rational64Ext::from((2, 1));
+        assert_eq!(big.checked_mul(&big), None);
+        assert_eq!(big.checked_add(&big), None);
+        assert_eq!(
+            Rational64Ext::from((1, i64::MAX)).checked_mul(&Rational64Ext::from((1, 2))),
+            None
+        );
+
+        assert_eq!(
+            big.checked_mul(&Rational64Ext::from((3, 1))),
+            Some(Rational64Ext::from((i64::MAX, 1)))
+        );
+        assert_eq!(two.checked_add(&two), Some(&two + &two));
+        assert_eq!(RatioExt::Inf.checked_mul(&big), Some(RatioExt::Inf));
+        assert_eq!(
+            RatioExt::MinusInf.checked_add(&big),
+            Some(RatioExt::MinusInf)
+        );
+        assert_eq!(
+            RatioExt::Inf.checked_mul(&Rational64Ext::zero()),
+            Some(Rational64Ext::zero())
+        );
+        assert!(RatioExt::Inf
+            .checked_add(&RatioExt::<i32>::MinusInf)
+            .unwrap()
+            .is_nan());
+    }
+
+    #[test]
+    fn display_with_precision() {
+        let third = RatioExt::<i64>::from((1, 3));
+
+        assert_str_eq!(format!("{third}"), "1/3");
+        assert_str_eq!(format!("{third:.2}"), "0.33");
+        assert_str_eq!(format!("{:.3}", RatioExt::<i64>::from((-5, 2))), "-2.500");
+        assert_str_eq!(format!("{:.2}", RatioExt::<i64>::Inf), "Inf");
+        assert_str_eq!(format!("{:.2}", RatioExt::<i64>::MinusInf), "-Inf");
+        assert_str_eq!(format!("{:.2}", RatioExt::<i64>::Nan), "NaN");
+    }
+
+    #[test]
+    fn assign_ops_with_ratio_agree_with_finite() {
+        let lhs_values = [
+            RatioExt::Inf,
+            RatioExt::MinusInf,
+            RatioExt::Nan,
+            RatioExt::zero(),
+            RatioExt::from((-7, 2)),
+            RatioExt::from((1, 3)),
+        ];
+        let rhs_values = [(0, 1), (1, 1), (-1, 1), (1, 3)].map(|(n, d)| Ratio::<i32>::new(n, d));
+
+        for lhs in &lhs_values {
+            for rhs in &rhs_values {
+                let rhs_ext = RatioExt::Finite(*rhs);
+
+                let (mut with_ratio, mut with_finite) = (lhs.clone(), lhs.clone());
+                with_ratio += *rhs;
+                with_finite += &rhs_ext;
+                assert!(with_ratio.bitwise_eq(&with_finite), "{lhs} += {rhs}");
+
+                let (mut with_ratio, mut with_finite) = (lhs.clone(), lhs.clone());
+                with_ratio -= *rhs;
+                with_finite -= &rhs_ext;
+                assert!(with_ratio.bitwise_eq(&with_finite), "{lhs} -= {rhs}");
+
+                let (mut with_ratio, mut with_finite) = (lhs.clone(), lhs.clone());
+                with_ratio *= *rhs;
+                with_finite *= &rhs_ext;
+                assert!(with_ratio.bitwise_eq(&with_finite), "{lhs} *= {rhs}");
+
+                let (mut with_ratio, mut with_finite) = (lhs.clone(), lhs.clone());
+                with_ratio /= *rhs;
+                with_finite /= &rhs_ext;
+                assert!(with_ratio.bitwise_eq(&with_finite), "{lhs} /= {rhs}");
+            }
+        }
+    }
+
+    #[test]
+    fn conversions_with_big_rational() {
+        use crate::BigRationalExt;
+        use num_rational::BigRational;
+
+        let ratio = BigRational::new((-7).into(), 2.into());
+        assert_eq!(
+            BigRationalExt::from(ratio.clone()),
+            BigRationalExt::Finite(ratio.clone())
+        );
+        assert_eq!(
+            BigRational::try_from(BigRationalExt::from(ratio.clone())),
+            Ok(ratio)
+        );
+
+        assert_eq!(
+            BigRational::try_from(BigRationalExt::Inf),
+            Err(BigRationalExt::Inf)
+        );
+        assert_eq!(
+            BigRational::try_from(BigRationalExt::MinusInf),
+            Err(BigRationalExt::MinusInf)
+        );
+        assert!(BigRational::try_from(BigRationalExt::Nan)
+            .unwrap_err()
+            .bitwise_eq(&BigRationalExt::Nan));
+    }
+
+    #[test]
+    fn from_ratio_and_into_finite() {
+        let ratio = Ratio::new(-7, 2);
+        assert_eq!(RatioExt::from_ratio(ratio), RatioExt::Finite(ratio));
+        assert_eq!(RatioExt::from_ratio(ratio).into_finite(), Ok(ratio));
+
+        assert_eq!(RatioExt::<i32>::Inf.into_finite(), Err(RatioExt::Inf));
+        assert_eq!(
+            RatioExt::<i32>::MinusInf.into_finite(),
+            Err(RatioExt::MinusInf)
+        );
+        assert!(RatioExt::<i32>::Nan
+            .into_finite()
+            .unwrap_err()
+            .bitwise_eq(&RatioExt::Nan));
+    }
+
+    #[test]
+    fn approx_eq() {
+        let tol = RatioExt::Finite(Ratio::new(1, 1000));
+        let third = RatioExt::Finite(Ratio::new(1, 3));
+        let approx = RatioExt::Finite(Ratio::new(3333, 10000));
+        assert!(third.approx_eq(&approx, &tol));
+        assert!(approx.approx_eq(&third, &tol));
+        assert!(!third.approx_eq(&RatioExt::Finite(Ratio::new(33, 100)), &tol));
+
+        assert!(RatioExt::<i32>::Inf.approx_eq(&RatioExt::Inf, &tol));
+        assert!(RatioExt::<i32>::MinusInf.approx_eq(&RatioExt::MinusInf, &tol));
+        assert!(!RatioExt::Inf.approx_eq(&RatioExt::MinusInf, &tol));
+        assert!(!third.approx_eq(&RatioExt::Inf, &RatioExt::Inf));
+        assert!(!RatioExt::<i32>::Nan.approx_eq(&RatioExt::Nan, &tol));
+        assert!(!third.approx_eq(&third, &RatioExt::Nan));
+    }
+
+    #[test]
+    fn sign_predicates() {
+        // (value, is_positive, is_negative, is_non_negative)
+        for (value, positive, negative, non_negative) in [
+            (RatioExt::Inf, true, false, true),
+            (RatioExt::Finite(Ratio::new(1, 3)), true, false, true),
+            (RatioExt::zero(), false, false, true),
+            (RatioExt::Finite(Ratio::new(-1, 3)), false, true, false),
+            (RatioExt::MinusInf, false, true, false),
+            (RatioExt::<i32>::Nan, false, false, false),
+        ] {
+            assert_eq!(value.is_positive(), positive, "{value}");
+            assert_eq!(value.is_negative(), negative, "{value}");
+            assert_eq!(value.is_non_negative(), non_negative, "{value}");
+        }
+    }
+
+    #[test]
+    fn from_str_radix() {
+        assert_eq!(
+            RatioExt::<i32>::from_str_radix("3/4", 10),
+            Ok(RatioExt::Finite(Ratio::new(3, 4)))
+        );
+        assert_eq!(
+            RatioExt::<i32>::from_str_radix("-Inf", 10),
+            Ok(RatioExt::MinusInf)
+        );
+        assert!(RatioExt::<i32>::from_str_radix("NaN", 10).unwrap().is_nan());
+        assert!(RatioExt::<i32>::from_str_radix("3/0", 10).is_err());
+
+        let err = RatioExt::<i32>::from_str_radix("11", 2).unwrap_err();
+        assert_eq!(err, ParseRatioExtError::Radix(2));
+        assert_str_eq!(err.to_string(), "Unsupported radix 2, only 10 is supported");
+    }
+
+    #[test]
+    fn rem() {
+        let ratio = |n, d| RatioExt::Finite(Ratio::new(n, d));
+        assert_eq!(ratio(7, 2) % ratio(1, 1), ratio(1, 2));
+        assert_eq!(ratio(7, 2) % RatioExt::Inf, ratio(7, 2));
+        assert!((ratio(7, 2) % RatioExt::zero()).is_nan());
+        assert!((RatioExt::Inf % ratio(7, 2)).is_nan());
+    }
+
+    #[test]
+    fn finite_or() {
+        let default = Ratio::new(1000, 1);
+        assert_eq!(
+            RatioExt::Finite(Ratio::new(-7, 2)).finite_or(default),
+            Ratio::new(-7, 2)
+        );
+        for value in [RatioExt::Inf, RatioExt::MinusInf, RatioExt::Nan] {
+            assert_eq!(value.finite_or(default), default, "{value}");
+        }
+    }
+
+    #[test]
+    fn powi() {
+        let ratio = |n: i64, d: i64| RatioExt::Finite(Ratio::new(n, d));
+        assert_eq!(ratio(-2, 3).powi(3), ratio(-8, 27));
+        assert_eq!(ratio(3, 2).powi(10), ratio(59049, 1024));
+        assert_eq!(ratio(-2, 3).powi(0), ratio(1, 1));
+        assert_eq!(ratio(-2, 3).powi(-2), ratio(9, 4));
+        assert_eq!(ratio(0, 1).powi(-1), RatioExt::Inf);
+
+        assert_eq!(RatioExt::<i64>::Inf.powi(0), ratio(1, 1));
+        assert_eq!(RatioExt::<i64>::Inf.powi(-1), ratio(0, 1));
+        assert_eq!(RatioExt::<i64>::Inf.powi(2), RatioExt::Inf);
+        assert_eq!(RatioExt::<i64>::MinusInf.powi(3), RatioExt::MinusInf);
+        assert_eq!(RatioExt::<i64>::MinusInf.powi(2), RatioExt::Inf);
+        assert_eq!(RatioExt::<i64>::MinusInf.powi(-3), ratio(0, 1));
+        assert_eq!(RatioExt::<i64>::Nan.powi(0), ratio(1, 1));
+        assert!(RatioExt::<i64>::Nan.powi(2).is_nan());
+    }
+
+    #[test]
+    fn comparison_with_integers() {
+        use crate::Rational64Ext;
+
+        let three = Rational64Ext::from_integer(3);
+        assert!(three == 3);
+        assert!(three != 4);
+        assert!(three < 10 && three > -1 && three <= 3 && three >= 3);
+        assert!(Rational64Ext::from((7, 2)) != 3);
+        assert!(Rational64Ext::from((7, 2)) > 3);
+
+        assert!(Rational64Ext::Inf > i64::MAX);
+        assert!(Rational64Ext::MinusInf < i64::MIN);
+        assert!(Rational64Ext::Inf != 0);
+        assert!(Rational64Ext::Nan != 0);
+        assert_eq!(Rational64Ext::Nan.partial_cmp(&0), None);
+    }
+
+    #[test]
+    fn lcm_denominators_of_the_finite_values() {
+        let ratio = |n: i64, d: i64| BigRationalExt::from((n.into(), d.into()));
+        assert_eq!(
+            lcm_denominators(&[ratio(1, 2), ratio(1, 3), ratio(5, 1)]),
+            Some(6.into())
+        );
+        assert_eq!(
+            lcm_denominators(&[ratio(3, 4), RatioExt::Inf, ratio(-1, 6), RatioExt::Nan]),
+            Some(12.into())
+        );
+        assert_eq!(lcm_denominators(&[RatioExt::MinusInf, RatioExt::Inf]), None);
+        assert_eq!(lcm_denominators(&[]), None);
+    }
+
+    #[test]
+    fn try_sum_and_try_product() {
+        let ratio = |n: i32| RatioExt::<i32>::from_integer(n);
+        assert_eq!(
+            vec![ratio(1), ratio(2), ratio(3)].into_iter().try_sum(),
+            Ok(ratio(6))
+        );
+        assert_eq!(
+            vec![ratio(1), RatioExt::Inf, ratio(3), RatioExt::MinusInf]
+                .into_iter()
+                .try_sum(),
+            Err(ArithmeticError::InfMinusInf)
+        );
+        // The `Sum` carries the indeterminate form through
+        assert!(vec![RatioExt::Inf, RatioExt::MinusInf]
+            .into_iter()
+            .sum::<RatioExt<i32>>()
+            .is_nan());
+        assert_eq!(
+            vec![ratio(1), RatioExt::Nan].into_iter().try_sum(),
+            Err(ArithmeticError::Nan)
+        );
+
+        assert_eq!(
+            vec![ratio(2), RatioExt::Inf].into_iter().try_product(),
+            Ok(RatioExt::Inf)
+        );
+        assert_eq!(
+            vec![ratio(0), ratio(2), RatioExt::MinusInf]
+                .into_iter()
+                .try_product(),
+            Err(ArithmeticError::ZeroTimesInf)
+        );
+        assert_eq!(
+            vec![RatioExt::Inf, ratio(0)].into_iter().try_product(),
+            Err(ArithmeticError::ZeroTimesInf)
+        );
+    }
 }
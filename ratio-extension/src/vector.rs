@@ -0,0 +1,48 @@
+use nalgebra::RowDVector;
+
+use crate::BigRationalExt;
+
+/// Row of the exact values of the `floats`, see [`BigRationalExt::from_float`]
+pub fn ratio_vec_from_floats(floats: &[f64]) -> RowDVector<BigRationalExt> {
+    RowDVector::from_iterator(
+        floats.len(),
+        floats.iter().copied().map(BigRationalExt::from_float),
+    )
+}
+
+/// Row of the values of the decimal strings, that keeps them exact, unlike the floats.
+///
+/// Fails on the first string, that [`BigRationalExt::from_decimal_str`] doesn't accept
+pub fn ratio_vec_from_decimals(decimals: &[&str]) -> Result<RowDVector<BigRationalExt>, String> {
+    decimals
+        .iter()
+        .map(|decimal| BigRationalExt::from_decimal_str(decimal))
+        .collect::<Result<Vec<_>, _>>()
+        .map(RowDVector::from_vec)
+}
+
+#[cfg(test)]
+mod tests {
+    use num_rational::BigRational;
+
+    use super::*;
+
+    #[test]
+    fn vectors_of_finite_values() {
+        let ratio = |n: i32, d: i32| BigRationalExt::Finite(BigRational::new(n.into(), d.into()));
+
+        assert_eq!(
+            ratio_vec_from_floats(&[0.5, -3., 0.]),
+            RowDVector::from_vec(vec![ratio(1, 2), ratio(-3, 1), ratio(0, 1)])
+        );
+        assert_eq!(
+            ratio_vec_from_decimals(&["0.1", "-12.25", "7"]),
+            Ok(RowDVector::from_vec(vec![
+                ratio(1, 10),
+                ratio(-49, 4),
+                ratio(7, 1)
+            ]))
+        );
+        assert!(ratio_vec_from_decimals(&["1", "x"]).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+//! Solves a problem from a JSON file without the UI:
+//!
+//! ```json
+//! {
+//!   "minimization": true,
+//!   "objective": [15, 70, "1/3"],
+//!   "constraints": [{ "coefficients": [1, 0, 2], "sign": ">=", "rhs": 5 }]
+//! }
+//! ```
+
+use std::{env, fs, process::ExitCode, str::FromStr};
+
+use course_project::simplex::{Constraint, ObjectiveFunction, Problem, Sign};
+use nalgebra::RowDVector;
+use num_rational::BigRational;
+use ratio_extension::BigRationalExt;
+use serde::Deserialize;
+
+const USAGE: &str = "Usage: tdm-solve [--max-iterations <N>] [--integer | --relax] <problem.json>";
+
+#[derive(Debug, Deserialize)]
+struct ProblemDescription {
+    minimization: bool,
+    objective: Vec<Number>,
+    constraints: Vec<ConstraintDescription>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConstraintDescription {
+    coefficients: Vec<Number>,
+    sign: String,
+    rhs: Number,
+}
+
+/// Either a JSON number, or an exact fraction, written as a string like `"1/3"`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Number {
+    Float(f64),
+    Exact(String),
+}
+
+#[derive(Debug)]
+struct Args {
+    path: String,
+    max_iterations: Option<u32>,
+    integer: bool,
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    let problem = match read_problem(&args.path) {
+        Ok(problem) => problem,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::from(2);
+        }
+    };
+    let problem = match args.max_iterations {
+        Some(max_iterations) => problem.with_max_iterations(max_iterations),
+        None => problem,
+    };
+
+    let (solution, stats) = if args.integer {
+        problem.solve_with_whole_and_stats()
+    } else {
+        problem.solve_with_stats()
+    };
+
+    println!("Iterations: {}", stats.iterations);
+    for warning in &stats.warnings {
+        println!("Warning: {warning}");
+    }
+    match solution {
+        Ok(solution) => {
+            println!("{solution}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut path = None;
+    let mut max_iterations = None;
+    let mut integer = true;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--max-iterations" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| "--max-iterations expects a value".to_owned())?;
+                max_iterations = Some(
+                    value
+                        .parse()
+                        .map_err(|err| format!("Invalid --max-iterations: {err}"))?,
+                );
+            }
+            "--integer" => integer = true,
+            "--relax" => integer = false,
+            "--two-phase" => {
+                return Err(
+                    "The two-phase method is not supported, the Big-M method is used".to_owned(),
+                )
+            }
+            _ if arg.starts_with("--") => return Err(format!("Unknown flag: {arg}")),
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(format!("Unexpected argument: {arg}")),
+        }
+    }
+
+    Ok(Args {
+        path: path.ok_or_else(|| "Missing the problem file".to_owned())?,
+        max_iterations,
+        integer,
+    })
+}
+
+fn read_problem(path: &str) -> Result<Problem, String> {
+    let description = fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+    let ProblemDescription {
+        minimization,
+        objective,
+        constraints,
+    } = serde_json::from_str(&description).map_err(|err| format!("{path}: {err}"))?;
+
+    let constraints = constraints
+        .into_iter()
+        .map(|constraint| {
+            Ok(Constraint::new(
+                row(constraint.coefficients)?,
+                // The signs are written as they are displayed
+                match [Sign::Less, Sign::Equals, Sign::Greater]
+                    .into_iter()
+                    .find(|sign| sign.to_string() == constraint.sign)
+                {
+                    Some(sign) => sign,
+                    None => return Err(format!("Unknown sign: {}", constraint.sign)),
+                },
+                number(constraint.rhs)?,
+            ))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Problem::new(
+        ObjectiveFunction::new(row(objective)?, minimization),
+        constraints,
+    )
+    .map_err(|err| err.to_string())
+}
+
+fn row(numbers: Vec<Number>) -> Result<RowDVector<BigRationalExt>, String> {
+    let numbers = numbers
+        .into_iter()
+        .map(number)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(RowDVector::from_vec(numbers))
+}
+
+fn number(number: Number) -> Result<BigRationalExt, String> {
+    match number {
+        Number::Float(float) => Ok(BigRationalExt::from_float(float)),
+        Number::Exact(exact) => BigRational::from_str(&exact)
+            .map(BigRationalExt::from)
+            .map_err(|err| format!("Invalid number {exact:?}: {err}")),
+    }
+}
@@ -1,25 +1,239 @@
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
 
-use nalgebra::{Const, DMatrix, DVector, Dynamic, RowDVector};
+use derive_more::Display;
+use lazy_static::lazy_static;
+use nalgebra::{DMatrix, DVector, Dim, Matrix, RawStorage, RowDVector};
 use num_rational::BigRational;
-use num_traits::{FromPrimitive, One, Zero};
+use num_traits::{FromPrimitive, Zero};
 use ratio_extension::BigRationalExt;
+use serde::{Deserialize, Serialize, Serializer};
 
-use crate::{ensure_eq, simplex};
+use crate::{ensure_eq, helpers, simplex};
 
-#[tauri::command]
+#[derive(Debug, Clone, PartialEq, Display, derive_more::Error, derive_more::IsVariant)]
+pub enum ComputeError {
+    #[display(fmt = "{_0}")]
+    Message(#[error(not(source))] Cow<'static, str>),
+    /// Even if every available ship served the line, its minimal transport would not be reached
+    #[display(
+        fmt = "Мінімальний обсяг перевезень на лінії {line} перевищує можливий обсяг {capacity}"
+    )]
+    LineCapacity {
+        /// Number of the line, starting from 1
+        line: usize,
+        capacity: BigRationalExt,
+    },
+    /// An entry of the input is [`RatioExt::Nan`](ratio_extension::RatioExt::Nan)
+    #[display(
+        fmt = "Некоректне значення у матриці {matrix} на позиції ({}, {})",
+        "index.0 + 1",
+        "index.1 + 1"
+    )]
+    InvalidValue {
+        matrix: InputMatrix,
+        /// Row and column of the entry, starting from 0
+        index: (usize, usize),
+    },
+    /// The [`problem_size`] exceeds the limit, so the branch-and-bound may run for too long.
+    /// The user has to confirm the solve
+    #[display(
+        fmt = "Задача завелика: оцінка складності {size} перевищує {limit}. Підтвердіть обчислення, щоб продовжити"
+    )]
+    TooLarge { size: u64, limit: u64 },
+    #[display(fmt = "Перевищено час обчислення")]
+    Timeout,
+    #[display(fmt = "Обчислення скасовано")]
+    Cancelled,
+}
+
+/// Input matrix of [`compute`], that is named in the [`ComputeError::InvalidValue`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum InputMatrix {
+    #[display(fmt = "обсягів перевезень")]
+    TransportRate,
+    #[display(fmt = "вартостей перевезень")]
+    CostRate,
+    #[display(fmt = "мінімальних обсягів перевезень")]
+    MinTransportPerLine,
+}
+
+lazy_static! {
+    /// Token of the running [`compute`], that [`cancel_compute`] cancels
+    static ref RUNNING_COMPUTE: Mutex<Option<simplex::CancellationToken>> = Mutex::new(None);
+}
+
+/// [`problem_size`], above which [`compute`] asks for the confirmation by default
+pub const DEFAULT_SIZE_LIMIT: u64 = 100_000;
+
+/// Name of the window event, that carries [`ComputeProgress`]
+pub const PROGRESS_EVENT: &str = "compute-progress";
+
+/// Branch-and-bound progress, reported while [`compute`] runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComputeProgress {
+    /// Number of the relaxations solved so far
+    pub nodes_explored: u32,
+    /// Objective of the incumbent in the sense of the problem
+    pub best_objective: Option<BigRational>,
+}
+
+/// How the fleet of every ship type is used: `sum_i n_ij = N_j` or `sum_i n_ij <= N_j`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FleetUsage {
+    /// Every ship is used
+    #[default]
+    Exact,
+    /// Some ships may stay unused
+    AtMost,
+}
+
+impl From<FleetUsage> for simplex::Sign {
+    fn from(fleet_usage: FleetUsage) -> Self {
+        match fleet_usage {
+            FleetUsage::Exact => simplex::Sign::Equals,
+            FleetUsage::AtMost => simplex::Sign::Less,
+        }
+    }
+}
+
+/// Result of the finished [`compute`].
+///
+/// The infeasible and the unbounded problems are valid answers too,
+/// so only the internal and the validation errors are [`ComputeError`]s
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum SolutionOutcome {
+    Finite {
+        /// Ships of every type on every line
+        matrix: DMatrix<BigRational>,
+        /// Objective in the sense of the problem
+        value: BigRational,
+        minimize: bool,
+    },
+    Infeasible,
+    Unbounded,
+}
+
+/// Nonzero cells of the [`SolutionOutcome::Finite`] matrix, which are easier to show, than the mostly zero matrix
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipAssignment {
+    /// `(line, ship, count)` in the row-major order, the indices start from 0
+    pub assignments: Vec<(usize, usize, BigRational)>,
+    /// Objective in the sense of the problem
+    pub total_cost: BigRational,
+}
+
+pub type ProgressSink = Arc<dyn Fn(ComputeProgress) + Send + Sync>;
+
+impl SolutionOutcome {
+    /// [`None`] for the infeasible and the unbounded problems
+    pub fn ship_assignment(&self) -> Option<ShipAssignment> {
+        let SolutionOutcome::Finite { matrix, value, .. } = self else {
+            return None;
+        };
+        let assignments = (0..matrix.nrows())
+            .flat_map(|line| (0..matrix.ncols()).map(move |ship| (line, ship)))
+            .filter(|index| !matrix[*index].is_zero())
+            .map(|(line, ship)| (line, ship, matrix[(line, ship)].clone()))
+            .collect();
+        Some(ShipAssignment {
+            assignments,
+            total_cost: value.clone(),
+        })
+    }
+}
+
+impl From<String> for ComputeError {
+    fn from(message: String) -> Self {
+        Self::Message(message.into())
+    }
+}
+
+/// Serialized as the message, that the frontend shows as is
+impl Serialize for ComputeError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Without `fleet_usage` every ship is used, as with [`FleetUsage::Exact`].
+/// Without `timeout_ms` the solve is not limited in time.
+/// A problem, whose [`problem_size`] exceeds the `size_limit`, or [`DEFAULT_SIZE_LIMIT`] without it,
+/// fails with [`ComputeError::TooLarge`], unless the user confirmed the solve with `allow_large`.
+/// The progress is emitted to the `window` as [`PROGRESS_EVENT`].
+///
+/// Runs outside of the main thread, so that [`cancel_compute`] can be invoked meanwhile
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
 pub fn compute(
+    window: tauri::Window,
     transport_rate: DMatrix<BigRationalExt>,
     cost_rate: DMatrix<BigRationalExt>,
     min_transport_per_line: DVector<BigRationalExt>,
     ships_count_per_type: RowDVector<u16>,
-) -> Result<(DMatrix<BigRational>, BigRational), Cow<'static, str>> {
+    minimize: bool,
+    fleet_usage: Option<FleetUsage>,
+    timeout_ms: Option<u64>,
+    size_limit: Option<u64>,
+    allow_large: Option<bool>,
+) -> Result<SolutionOutcome, ComputeError> {
+    compute_observed(
+        transport_rate,
+        cost_rate,
+        min_transport_per_line,
+        ships_count_per_type,
+        minimize,
+        fleet_usage.unwrap_or_default(),
+        match allow_large {
+            Some(true) => None,
+            _ => Some(size_limit.unwrap_or(DEFAULT_SIZE_LIMIT)),
+        },
+        timeout_ms,
+        Arc::new(move |progress| {
+            if let Err(err) = window.emit(PROGRESS_EVENT, progress) {
+                log::error!("{err:?}");
+            }
+        }),
+    )
+}
+
+/// Same as [`compute`], but reports the progress to `on_progress`.
+/// Without `size_limit` a problem of any size is solved
+#[allow(clippy::too_many_arguments)]
+pub fn compute_observed(
+    transport_rate: DMatrix<BigRationalExt>,
+    cost_rate: DMatrix<BigRationalExt>,
+    min_transport_per_line: DVector<BigRationalExt>,
+    ships_count_per_type: RowDVector<u16>,
+    minimize: bool,
+    fleet_usage: FleetUsage,
+    size_limit: Option<u64>,
+    timeout_ms: Option<u64>,
+    on_progress: ProgressSink,
+) -> Result<SolutionOutcome, ComputeError> {
     log::info!(
         "Received input:\n\
-        transport_rate:\n{transport_rate}\n\
-        cost_rate:\n{cost_rate}\n\
+        transport_rate:\n{}\n\
+        cost_rate:\n{}\n\
         min_transport_per_line:\n{min_transport_per_line}\n\
-        ships_count_per_type:\n{ships_count_per_type}"
+        ships_count_per_type:\n{ships_count_per_type}\n\
+        minimize: {minimize}\n\
+        fleet_usage: {fleet_usage:?}\n\
+        size_limit: {size_limit:?}\n\
+        timeout_ms: {timeout_ms:?}",
+        helpers::format_matrix(&transport_rate),
+        helpers::format_matrix(&cost_rate),
     );
 
     ensure_eq!(transport_rate.shape(), cost_rate.shape());
@@ -31,81 +245,753 @@ pub fn compute(
     let n_ships = ships_count_per_type.ncols();
     log::info!("n_lines: {n_lines}\nn_ships: {n_ships}");
 
-    let problem = simplex::Problem::new(
-        simplex::ObjectiveFunction::new(
-            cost_rate
-                .transpose()
-                .reshape_generic(Const::<1>, Dynamic::new(n_lines * n_ships)),
-            true,
-        ),
-        construct_constraints(
-            transport_rate,
-            min_transport_per_line,
-            ships_count_per_type,
-            n_ships,
-            n_lines,
+    check_values(&transport_rate, &cost_rate, &min_transport_per_line)?;
+    check_line_capacities(
+        &transport_rate,
+        &cost_rate,
+        &min_transport_per_line,
+        &ships_count_per_type,
+    )?;
+    check_size(n_lines, n_ships, &ships_count_per_type, size_limit)?;
+
+    let problem = construct_problem(
+        transport_rate,
+        cost_rate,
+        min_transport_per_line,
+        ships_count_per_type,
+        minimize,
+        fleet_usage.into(),
+    )
+    .map_err(|err| err.to_string())?;
+    log::info!("Problem formed: {}", problem.summary());
+    log::debug!("{problem}");
+
+    let token = simplex::CancellationToken::new();
+    *RUNNING_COMPUTE.lock().unwrap() = Some(token.clone());
+    let problem = problem.with_cancellation(token.clone());
+    let relaxation = problem.clone();
+    let sink = progress_sink(minimize, on_progress);
+    let solution = match timeout_ms {
+        Some(timeout_ms) => solve_with_timeout(
+            problem,
+            sink,
+            token.clone(),
+            Duration::from_millis(timeout_ms),
         ),
-    );
-    log::info!("Problem formed: {problem}");
+        None => Ok(problem.solve_with_whole_observed(&sink)),
+    };
+    {
+        let mut running_compute = RUNNING_COMPUTE.lock().unwrap();
+        if running_compute.as_ref() == Some(&token) {
+            *running_compute = None;
+        }
+    }
+    let solution = solution?;
 
-    let solution = problem.solve_with_whole();
+    solution_outcome(solution, minimize, (n_lines, n_ships), || {
+        relaxation.feasible_point().is_some()
+    })
+}
 
+/// Tells the solution statuses, that the frontend shows, from the errors.
+///
+/// The infinite objective is left by the positive artificial variables too,
+/// so it is only unbounded, if `is_feasible` finds a feasible point of the relaxation
+fn solution_outcome(
+    solution: simplex::SolutionResult,
+    minimize: bool,
+    (n_lines, n_ships): (usize, usize),
+    is_feasible: impl FnOnce() -> bool,
+) -> Result<SolutionOutcome, ComputeError> {
     match solution {
         Ok(solution) => {
             log::info!("Solution:\n{solution}");
-            let simplex::Solution { vars, fn_val } = solution;
-            Ok((
-                DMatrix::from_row_iterator(n_lines, n_ships, vars.into_iter()),
-                fn_val,
-            ))
+            let value = solution.objective_in(minimize);
+            Ok(SolutionOutcome::Finite {
+                matrix: DMatrix::from_row_iterator(n_lines, n_ships, solution.into_iter()),
+                value,
+                minimize,
+            })
         }
+        Err(simplex::SolutionError::Absent) => Ok(SolutionOutcome::Infeasible),
+        Err(simplex::SolutionError::Infinite) if is_feasible() => Ok(SolutionOutcome::Unbounded),
+        Err(simplex::SolutionError::Infinite) => Ok(SolutionOutcome::Infeasible),
+        Err(simplex::SolutionError::Cancelled) => Err(ComputeError::Cancelled),
         Err(non_compliant) => Err(non_compliant.to_string().into()),
     }
 }
 
-fn construct_constraints(
+/// Cancels the running [`compute`], if there is one
+#[tauri::command]
+pub fn cancel_compute() {
+    if let Some(token) = &*RUNNING_COMPUTE.lock().unwrap() {
+        log::info!("Cancelling the compute");
+        token.cancel();
+    }
+}
+
+/// Logs the events and counts the solved relaxations, every one of which starts at the first iteration
+fn progress_sink(
+    minimize: bool,
+    on_progress: ProgressSink,
+) -> impl Fn(simplex::SolveEvent) + Send + Sync + 'static {
+    let progress = Mutex::new(ComputeProgress::default());
+    move |event| {
+        let changed = {
+            let mut progress = progress.lock().unwrap();
+            match &event {
+                simplex::SolveEvent::IterationStarted { n: 1, .. } => {
+                    progress.nodes_explored += 1;
+                    Some(progress.clone())
+                }
+                simplex::SolveEvent::IncumbentFound { objective, .. } => {
+                    progress.best_objective = Some(if minimize {
+                        objective.clone()
+                    } else {
+                        -objective.clone()
+                    });
+                    Some(progress.clone())
+                }
+                _ => None,
+            }
+        };
+        if let Some(progress) = changed {
+            on_progress(progress);
+        }
+        simplex::log_event(event);
+    }
+}
+
+/// Solves on a worker thread, which is cancelled with the `token`, once the `timeout` elapses
+fn solve_with_timeout(
+    problem: simplex::Problem,
+    sink: impl Fn(simplex::SolveEvent) + Send + Sync + 'static,
+    token: simplex::CancellationToken,
+    timeout: Duration,
+) -> Result<simplex::SolutionResult, ComputeError> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || sender.send(problem.solve_with_whole_observed(&sink)));
+
+    match receiver.recv_timeout(timeout) {
+        Ok(solution) => Ok(solution),
+        Err(RecvTimeoutError::Timeout) => {
+            log::info!("Timeout of {timeout:?} elapsed. Cancelling.");
+            token.cancel();
+            Err(ComputeError::Timeout)
+        }
+        Err(RecvTimeoutError::Disconnected) => {
+            log::error!("The solver thread panicked");
+            Err(ComputeError::Message(
+                "Розв'язувач завершився з помилкою".into(),
+            ))
+        }
+    }
+}
+
+/// Fails on the first `NaN` entry, which would turn the objective function or the constraints into `NaN`.
+/// The infinities are left to the other checks, as the infinite cost marks an unavailable ship-line pair
+fn check_values(
+    transport_rate: &DMatrix<BigRationalExt>,
+    cost_rate: &DMatrix<BigRationalExt>,
+    min_transport_per_line: &DVector<BigRationalExt>,
+) -> Result<(), ComputeError> {
+    let invalid = [
+        (InputMatrix::TransportRate, nan_index(transport_rate)),
+        (InputMatrix::CostRate, nan_index(cost_rate)),
+        (
+            InputMatrix::MinTransportPerLine,
+            nan_index(min_transport_per_line),
+        ),
+    ]
+    .into_iter()
+    .find_map(|(matrix, index)| index.map(|index| ComputeError::InvalidValue { matrix, index }));
+    match invalid {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Row and column of the first `NaN` in the column-major order
+fn nan_index<R: Dim, C: Dim, S: RawStorage<BigRationalExt, R, C>>(
+    values: &Matrix<BigRationalExt, R, C, S>,
+) -> Option<(usize, usize)> {
+    let k = values.iter().position(BigRationalExt::is_nan)?;
+    Some((k % values.nrows(), k / values.nrows()))
+}
+
+/// Rough estimate of the branch-and-bound work: the number of the integer variables `n_lines * n_ships`
+/// times the number of the ships, which bounds the values, that every variable is branched on
+pub fn problem_size(n_lines: usize, n_ships: usize, ships_count_per_type: &RowDVector<u16>) -> u64 {
+    let total_ships = ships_count_per_type
+        .iter()
+        .map(|count| u64::from(*count))
+        .sum::<u64>();
+    (n_lines as u64)
+        .saturating_mul(n_ships as u64)
+        .saturating_mul(total_ships)
+}
+
+fn check_size(
+    n_lines: usize,
+    n_ships: usize,
+    ships_count_per_type: &RowDVector<u16>,
+    size_limit: Option<u64>,
+) -> Result<(), ComputeError> {
+    let size = problem_size(n_lines, n_ships, ships_count_per_type);
+    log::info!("Problem size: {size}");
+    match size_limit {
+        Some(limit) if size > limit => Err(ComputeError::TooLarge { size, limit }),
+        _ => Ok(()),
+    }
+}
+
+/// Fails on the first line, whose minimal transport exceeds `sum_j a_ij * N_j` over the available ships.
+/// Such a problem is infeasible, which the solver would only report as the absent solution
+fn check_line_capacities(
+    transport_rate: &DMatrix<BigRationalExt>,
+    cost_rate: &DMatrix<BigRationalExt>,
+    min_transport_per_line: &DVector<BigRationalExt>,
+    ships_count_per_type: &RowDVector<u16>,
+) -> Result<(), ComputeError> {
+    for (i, required) in min_transport_per_line.iter().enumerate() {
+        let capacity = transport_rate
+            .row(i)
+            .iter()
+            .zip(cost_rate.row(i).iter())
+            .zip(ships_count_per_type.iter())
+            // The infinite cost marks an unavailable ship-line pair
+            .filter(|((_, cost), _)| cost.is_finite())
+            .map(|((rate, _), count)| rate * &BigRationalExt::from_u16(*count).unwrap())
+            .sum::<BigRationalExt>();
+        if required > &capacity {
+            return Err(ComputeError::LineCapacity {
+                line: i + 1,
+                capacity,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn construct_problem(
     transport_rate: DMatrix<BigRationalExt>,
+    cost_rate: DMatrix<BigRationalExt>,
     min_transport_per_line: DVector<BigRationalExt>,
     ships_count_per_type: RowDVector<u16>,
-    n_ships: usize,
-    n_lines: usize,
-) -> Vec<simplex::Constraint> {
-    transport_rate
-        .row_iter()
-        .enumerate()
-        .map(|(i, row)| {
-            let lines_constraint = row
-                .insert_columns(0, dbg!(n_ships * i), Zero::zero())
-                .insert_columns(
-                    dbg!(n_ships * (i + 1)),
-                    dbg!(n_ships * (n_lines - i - 1)),
-                    Zero::zero(),
-                );
-            log::info!("{lines_constraint}");
-            lines_constraint
-        })
-        .zip(min_transport_per_line.row_iter())
-        .map(|(coefficients, rhs)| {
-            simplex::Constraint::new(coefficients, simplex::Sign::Greater, rhs.x.to_owned())
-        })
-        .chain({
-            let mut coefficients = Vec::with_capacity(n_ships);
-            coefficients.push(One::one());
-            coefficients.resize(n_ships, Zero::zero());
-            let block = coefficients.clone();
-            for _ in 0..n_lines - 1 {
-                coefficients.extend_from_slice(&block);
+    minimize: bool,
+    ships_count_sign: simplex::Sign,
+) -> Result<simplex::Problem, simplex::ProblemError> {
+    let (objective_function, constraints) = simplex::build_ship_model(
+        &transport_rate,
+        &cost_rate,
+        &min_transport_per_line,
+        &ships_count_per_type,
+        minimize,
+        ships_count_sign,
+    );
+    simplex::Problem::new(objective_function, constraints)
+}
+
+#[cfg(test)]
+mod tests {
+    use num_traits::One;
+
+    use super::*;
+
+    lazy_static! {
+        /// [`RUNNING_COMPUTE`] is global, so the tests, that call [`compute`], take turns
+        static ref SERIAL: Mutex<()> = Mutex::new(());
+    }
+
+    /// The variant of the course project, that takes a while to branch
+    fn compute_ships(
+        timeout_ms: Option<u64>,
+        on_progress: ProgressSink,
+    ) -> Result<SolutionOutcome, ComputeError> {
+        let from_floats = |nrows, ncols, floats: &[f64]| {
+            DMatrix::from_row_iterator(
+                nrows,
+                ncols,
+                floats.iter().copied().map(BigRationalExt::from_float),
+            )
+        };
+
+        compute_observed(
+            from_floats(
+                4,
+                3,
+                &[15., 30., 25., 10., 25., 50., 20., 10., 30., 50., 17., 45.],
+            ),
+            from_floats(
+                4,
+                3,
+                &[15., 70., 40., 20., 23., 70., 25., 15., 40., 40., 45., 65.],
+            ),
+            from_floats(4, 1, &[300., 200., 1000., 500.])
+                .column(0)
+                .into_owned(),
+            RowDVector::from_row_slice(&[50, 20, 30]),
+            true,
+            FleetUsage::Exact,
+            Some(DEFAULT_SIZE_LIMIT),
+            timeout_ms,
+            on_progress,
+        )
+    }
+
+    fn compute_two_lines(minimize: bool) -> SolutionOutcome {
+        compute_observed(
+            DMatrix::from_element(2, 1, BigRationalExt::from_float(10.)),
+            DMatrix::from_column_slice(
+                2,
+                1,
+                &[
+                    BigRationalExt::from_float(3.),
+                    BigRationalExt::from_float(5.),
+                ],
+            ),
+            DVector::zeros(2),
+            RowDVector::from_element(1, 2),
+            minimize,
+            FleetUsage::Exact,
+            None,
+            None,
+            Arc::new(|_| {}),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn compute_passes_the_sense_to_the_objective_function() {
+        let _serial = SERIAL.lock().unwrap();
+        for minimize in [true, false] {
+            let problem = construct_problem(
+                DMatrix::from_element(2, 1, BigRationalExt::one()),
+                DMatrix::from_element(2, 1, BigRationalExt::one()),
+                DVector::zeros(2),
+                RowDVector::from_element(1, 2),
+                minimize,
+                simplex::Sign::Equals,
+            )
+            .unwrap();
+            assert_eq!(problem.objective_function.minimization, minimize);
+        }
+
+        let int = |n: i32| BigRational::from_integer(n.into());
+        assert_eq!(
+            compute_two_lines(true),
+            SolutionOutcome::Finite {
+                matrix: DMatrix::from_column_slice(2, 1, &[int(2), int(0)]),
+                value: int(6),
+                minimize: true
+            }
+        );
+        assert_eq!(
+            compute_two_lines(false),
+            SolutionOutcome::Finite {
+                matrix: DMatrix::from_column_slice(2, 1, &[int(0), int(2)]),
+                value: int(10),
+                minimize: false
             }
-            drop(block);
-            ships_count_per_type.column_iter().map(move |count| {
-                let constraint = simplex::Constraint::new(
-                    RowDVector::from_row_slice(&coefficients),
-                    simplex::Sign::Equals,
-                    BigRationalExt::from_u16(count.x).unwrap(),
-                );
-                coefficients.rotate_right(1);
-                constraint
+        );
+    }
+
+    #[test]
+    fn compute_leaves_ships_unused_at_most_fleet_usage() {
+        let _serial = SERIAL.lock().unwrap();
+        // One ship is enough for the line, but there are three of them
+        let compute_one_line = |fleet_usage| {
+            compute_observed(
+                DMatrix::from_element(1, 1, BigRationalExt::from_float(10.)),
+                DMatrix::from_element(1, 1, BigRationalExt::from_float(3.)),
+                DVector::from_element(1, BigRationalExt::from_float(10.)),
+                RowDVector::from_element(1, 3),
+                true,
+                fleet_usage,
+                None,
+                None,
+                Arc::new(|_| {}),
+            )
+            .unwrap()
+        };
+
+        let int = |n: i32| BigRational::from_integer(n.into());
+        assert_eq!(
+            compute_one_line(FleetUsage::Exact),
+            SolutionOutcome::Finite {
+                matrix: DMatrix::from_element(1, 1, int(3)),
+                value: int(9),
+                minimize: true
+            }
+        );
+        assert_eq!(
+            compute_one_line(FleetUsage::AtMost),
+            SolutionOutcome::Finite {
+                matrix: DMatrix::from_element(1, 1, int(1)),
+                value: int(3),
+                minimize: true
+            }
+        );
+    }
+
+    #[test]
+    fn compute_fixes_the_unavailable_ship_line_pairs_to_zero() {
+        let _serial = SERIAL.lock().unwrap();
+        let transport_rate = DMatrix::from_element(2, 2, BigRationalExt::from_float(10.));
+        // The first ship is cheaper on the first line, but it is unavailable there
+        let cost_rate = DMatrix::from_row_slice(
+            2,
+            2,
+            &[
+                BigRationalExt::Inf,
+                BigRationalExt::from_float(3.),
+                BigRationalExt::from_float(4.),
+                BigRationalExt::from_float(5.),
+            ],
+        );
+        let min_transport_per_line = DVector::from_element(2, BigRationalExt::from_float(10.));
+        let ships_count_per_type = RowDVector::from_element(2, 1);
+
+        let problem = construct_problem(
+            transport_rate.clone(),
+            cost_rate.clone(),
+            min_transport_per_line.clone(),
+            ships_count_per_type.clone(),
+            true,
+            simplex::Sign::Equals,
+        )
+        .unwrap();
+        assert!(problem
+            .objective_function
+            .coefficients
+            .iter()
+            .all(|coefficient| coefficient.small_part().is_finite()));
+
+        let int = |n: i32| BigRational::from_integer(n.into());
+        assert_eq!(
+            compute_observed(
+                transport_rate,
+                cost_rate,
+                min_transport_per_line,
+                ships_count_per_type,
+                true,
+                FleetUsage::Exact,
+                None,
+                None,
+                Arc::new(|_| {}),
+            ),
+            Ok(SolutionOutcome::Finite {
+                matrix: DMatrix::from_row_slice(2, 2, &[int(0), int(1), int(1), int(0)]),
+                value: int(7),
+                minimize: true
             })
+        );
+    }
+
+    #[test]
+    fn errors_are_boxed_as_std_errors() {
+        let error: Box<dyn std::error::Error> = Box::new(simplex::SolutionError::Problem(
+            simplex::ProblemError::NoConstraints,
+        ));
+        assert_eq!(
+            error.to_string(),
+            simplex::ProblemError::NoConstraints.to_string()
+        );
+        assert!(error.source().is_some());
+
+        let error: Box<dyn std::error::Error> = Box::new(simplex::SolutionError::Infinite);
+        assert_eq!(error.to_string(), "Розв'язок нескінченний");
+        assert!(error.source().is_none());
+
+        let error: Box<dyn std::error::Error> = Box::new(ComputeError::Timeout);
+        assert_eq!(error.to_string(), "Перевищено час обчислення");
+        let error: Box<dyn std::error::Error> = Box::new(ComputeError::from("Помилка".to_owned()));
+        assert_eq!(error.to_string(), "Помилка");
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn compute_asks_to_confirm_the_large_problems() {
+        let _serial = SERIAL.lock().unwrap();
+
+        // 20 lines, 10 ship types of 100 ships each
+        let ships_count_per_type = RowDVector::from_element(10, 100);
+        let error = compute_observed(
+            DMatrix::from_element(20, 10, BigRationalExt::from_float(1.)),
+            DMatrix::from_element(20, 10, BigRationalExt::from_float(1.)),
+            DVector::zeros(20),
+            ships_count_per_type.clone(),
+            true,
+            FleetUsage::Exact,
+            Some(DEFAULT_SIZE_LIMIT),
+            None,
+            Arc::new(|_| {}),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            ComputeError::TooLarge {
+                size: 200_000,
+                limit: DEFAULT_SIZE_LIMIT,
+            }
+        );
+        // Confirmed by the user
+        assert_eq!(check_size(20, 10, &ships_count_per_type, None), Ok(()));
+
+        // The example problem is well below the limit
+        assert!(compute_ships(None, Arc::new(|_| {})).is_ok());
+    }
+
+    #[test]
+    fn compute_rejects_the_nan_entries() {
+        let mut cost_rate = DMatrix::from_element(2, 2, BigRationalExt::from_float(3.));
+        cost_rate[(1, 0)] = BigRationalExt::Nan;
+
+        let error = compute_observed(
+            DMatrix::from_element(2, 2, BigRationalExt::from_float(10.)),
+            cost_rate,
+            DVector::zeros(2),
+            RowDVector::from_row_slice(&[3, 2]),
+            true,
+            FleetUsage::Exact,
+            None,
+            None,
+            Arc::new(|_| {}),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            ComputeError::InvalidValue {
+                matrix: InputMatrix::CostRate,
+                index: (1, 0),
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Некоректне значення у матриці вартостей перевезень на позиції (2, 1)"
+        );
+
+        assert_eq!(
+            check_values(
+                &DMatrix::zeros(2, 1),
+                &DMatrix::zeros(2, 1),
+                &DVector::from_row_slice(&[BigRationalExt::from_float(0.), BigRationalExt::Nan]),
+            ),
+            Err(ComputeError::InvalidValue {
+                matrix: InputMatrix::MinTransportPerLine,
+                index: (1, 0),
+            })
+        );
+    }
+
+    #[test]
+    fn compute_reports_the_line_beyond_the_fleet_capacity() {
+        let _serial = SERIAL.lock().unwrap();
+        let transport_rate = DMatrix::from_element(2, 2, BigRationalExt::from_float(10.));
+        // The second ship can't serve the second line, so it gets at most 10 * 3
+        let cost_rate = DMatrix::from_row_slice(
+            2,
+            2,
+            &[
+                BigRationalExt::from_float(3.),
+                BigRationalExt::from_float(4.),
+                BigRationalExt::from_float(5.),
+                BigRationalExt::Inf,
+            ],
+        );
+        let min_transport_per_line = DVector::from_row_slice(&[
+            BigRationalExt::from_float(10.),
+            BigRationalExt::from_float(40.),
+        ]);
+
+        let error = compute_observed(
+            transport_rate,
+            cost_rate,
+            min_transport_per_line,
+            RowDVector::from_row_slice(&[3, 2]),
+            true,
+            FleetUsage::Exact,
+            None,
+            None,
+            Arc::new(|_| {}),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            ComputeError::LineCapacity {
+                line: 2,
+                capacity: BigRationalExt::from_float(30.),
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Мінімальний обсяг перевезень на лінії 2 перевищує можливий обсяг 30"
+        );
+    }
+
+    #[test]
+    fn compute_returns_the_infeasible_and_the_unbounded_outcomes() {
+        let _serial = SERIAL.lock().unwrap();
+        // The only ship can serve either of the lines, but not both
+        assert_eq!(
+            compute_observed(
+                DMatrix::from_element(2, 1, BigRationalExt::from_float(10.)),
+                DMatrix::from_element(2, 1, BigRationalExt::from_float(3.)),
+                DVector::from_element(2, BigRationalExt::from_float(10.)),
+                RowDVector::from_element(1, 1),
+                true,
+                FleetUsage::Exact,
+                None,
+                None,
+                Arc::new(|_| {}),
+            ),
+            Ok(SolutionOutcome::Infeasible)
+        );
+
+        // The fleet bounds every variable, so only the solver itself reports the unbounded objective
+        assert_eq!(
+            solution_outcome(Err(simplex::SolutionError::Infinite), false, (1, 1), || {
+                true
+            }),
+            Ok(SolutionOutcome::Unbounded)
+        );
+        assert_eq!(
+            solution_outcome(
+                Err(simplex::SolutionError::IterationLimit),
+                false,
+                (1, 1),
+                || true
+            ),
+            Err(ComputeError::Message(
+                "Перевищено максимальну кількість ітерацій".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn solution_outcome_is_tagged_by_the_status() {
+        let json = serde_json::to_value(SolutionOutcome::Infeasible).unwrap();
+        assert_eq!(json, serde_json::json!({ "status": "infeasible" }));
+
+        let json = serde_json::to_value(SolutionOutcome::Finite {
+            matrix: DMatrix::zeros(1, 1),
+            value: BigRational::one(),
+            minimize: true,
         })
-        .collect()
+        .unwrap();
+        assert_eq!(json["status"], "finite");
+        assert_eq!(json["minimize"], true);
+    }
+
+    #[test]
+    fn ship_assignment_lists_the_nonzero_cells() {
+        let _serial = SERIAL.lock().unwrap();
+        let outcome = compute_ships(None, Arc::new(|_| {})).unwrap();
+        let SolutionOutcome::Finite { matrix, value, .. } = &outcome else {
+            panic!("The ships problem has a solution, got {outcome:?}");
+        };
+
+        let ShipAssignment {
+            assignments,
+            total_cost,
+        } = outcome.ship_assignment().unwrap();
+        assert_eq!(&total_cost, value);
+        assert_eq!(
+            assignments.len(),
+            matrix.iter().filter(|count| !count.is_zero()).count()
+        );
+        for (line, ship, count) in &assignments {
+            assert!(!count.is_zero());
+            assert_eq!(count, &matrix[(*line, *ship)]);
+        }
+        assert_eq!(SolutionOutcome::Unbounded.ship_assignment(), None);
+    }
+
+    #[test]
+    fn compute_times_out_on_a_slow_problem() {
+        let _serial = SERIAL.lock().unwrap();
+
+        // The solver is held at its first progress, so it can't finish before the timeout
+        let (release, held) = mpsc::channel::<()>();
+        let held = Mutex::new(held);
+        assert_eq!(
+            compute_ships(
+                Some(1),
+                Arc::new(move |_| {
+                    let _ = held.lock().unwrap().recv();
+                })
+            ),
+            Err(ComputeError::Timeout)
+        );
+        drop(release);
+
+        let result = compute_observed(
+            DMatrix::zeros(1, 1),
+            DMatrix::zeros(1, 2),
+            DVector::zeros(1),
+            RowDVector::zeros(1),
+            true,
+            FleetUsage::Exact,
+            None,
+            Some(1),
+            Arc::new(|_| {}),
+        );
+        assert!(matches!(result, Err(ComputeError::Message(_))));
+    }
+
+    #[test]
+    fn compute_reports_the_panicked_solver() {
+        let _serial = SERIAL.lock().unwrap();
+
+        assert_eq!(
+            compute_ships(
+                Some(60_000),
+                Arc::new(|_| panic!("The progress can't be reported"))
+            ),
+            Err(ComputeError::Message(
+                "Розв'язувач завершився з помилкою".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn cancel_compute_stops_the_running_compute() {
+        let _serial = SERIAL.lock().unwrap();
+
+        let compute = thread::spawn(|| compute_ships(None, Arc::new(|_| {})));
+        while RUNNING_COMPUTE.lock().unwrap().is_none() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        cancel_compute();
+
+        assert_eq!(compute.join().unwrap(), Err(ComputeError::Cancelled));
+        assert!(RUNNING_COMPUTE.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn compute_reports_the_progress() {
+        let _serial = SERIAL.lock().unwrap();
+
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let outcome = compute_ships(None, {
+            let reported = reported.clone();
+            Arc::new(move |progress| reported.lock().unwrap().push(progress))
+        });
+        let Ok(SolutionOutcome::Finite { value, .. }) = outcome else {
+            panic!("The ships problem has a solution, got {outcome:?}");
+        };
+
+        let reported = reported.lock().unwrap();
+        assert_eq!(
+            reported[0],
+            ComputeProgress {
+                nodes_explored: 1,
+                best_objective: None,
+            }
+        );
+        assert!(reported
+            .windows(2)
+            .all(|pair| pair[0].nodes_explored <= pair[1].nodes_explored));
+        assert_eq!(reported.last().unwrap().best_objective, Some(value));
+    }
 }
@@ -1,6 +1,8 @@
 mod compute;
+mod persist;
 
 pub use compute::*;
+pub use persist::*;
 
 use nalgebra::DMatrix;
 use num_rational::Rational64;
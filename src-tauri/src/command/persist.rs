@@ -0,0 +1,135 @@
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use tauri::AppHandle;
+
+use super::{ComputeError, SolutionOutcome};
+
+/// Writes the `solution` to the file at the relative `path` in the data directory of the app,
+/// as JSON, that keeps every value exact
+#[tauri::command]
+pub fn save_solution(
+    app_handle: AppHandle,
+    path: PathBuf,
+    solution: SolutionOutcome,
+) -> Result<(), ComputeError> {
+    write_solution(&data_path(&app_handle, &path)?, &solution)
+}
+
+/// Reads the solution, that [`save_solution`] wrote to the file at the relative `path`
+#[tauri::command]
+pub fn load_solution(
+    app_handle: AppHandle,
+    path: PathBuf,
+) -> Result<SolutionOutcome, ComputeError> {
+    read_solution(&data_path(&app_handle, &path)?)
+}
+
+/// Path of the file in the data directory of the app.
+/// The webview is not trusted with the other files, so only the plain relative `path` is accepted
+fn data_path(app_handle: &AppHandle, path: &Path) -> Result<PathBuf, ComputeError> {
+    let data_dir = app_handle
+        .path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| "Не вдалося визначити каталог даних застосунку".to_owned())?;
+    resolve_in(&data_dir, path)
+}
+
+/// Joins the `path` to the `dir`, unless it is empty, absolute or leads out of the `dir` with `..`
+fn resolve_in(dir: &Path, path: &Path) -> Result<PathBuf, ComputeError> {
+    let mut components = path.components().peekable();
+    if components.peek().is_none() || !components.all(|c| matches!(c, Component::Normal(_))) {
+        return Err(format!("Некоректний шлях до файлу розв'язку: {}", path.display()).into());
+    }
+    Ok(dir.join(path))
+}
+
+fn write_solution(path: &Path, solution: &SolutionOutcome) -> Result<(), ComputeError> {
+    let json = serde_json::to_string(solution)
+        .map_err(|err| format!("Не вдалося серіалізувати розв'язок: {err}"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|err| format!("Не вдалося створити каталог {}: {err}", dir.display()))?;
+    }
+    fs::write(path, json)
+        .map_err(|err| format!("Не вдалося зберегти розв'язок у {}: {err}", path.display()))?;
+    log::info!("Saved the solution to {}", path.display());
+    Ok(())
+}
+
+fn read_solution(path: &Path) -> Result<SolutionOutcome, ComputeError> {
+    let json = fs::read_to_string(path)
+        .map_err(|err| format!("Не вдалося прочитати розв'язок з {}: {err}", path.display()))?;
+    let solution = serde_json::from_str(&json)
+        .map_err(|err| format!("Некоректний файл розв'язку {}: {err}", path.display()))?;
+    log::info!("Loaded the solution from {}", path.display());
+    Ok(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use nalgebra::DMatrix;
+    use num_rational::BigRational;
+
+    use super::*;
+    use crate::simplex;
+
+    #[test]
+    fn finite_solution_survives_the_round_trip() {
+        let third = BigRational::new(1.into(), 3.into());
+        let huge = BigRational::new("123456789012345678901234567890".parse().unwrap(), 7.into());
+        let solution = SolutionOutcome::Finite {
+            matrix: DMatrix::from_row_slice(1, 2, &[third.clone(), huge.clone()]),
+            value: -huge.clone(),
+            minimize: false,
+        };
+        let path = env::temp_dir().join(format!("tdm-solution-{}.json", process::id()));
+
+        write_solution(&path, &solution).unwrap();
+        let loaded = read_solution(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, Ok(solution));
+
+        let solution = simplex::Solution {
+            fn_val: huge.clone(),
+            vars: vec![third, huge],
+        };
+        let json = serde_json::to_string(&solution).unwrap();
+        assert_eq!(
+            serde_json::from_str::<simplex::Solution>(&json).unwrap(),
+            solution
+        );
+    }
+
+    #[test]
+    fn missing_solution_file_is_an_error() {
+        let path = env::temp_dir().join("tdm-solution-that-does-not-exist.json");
+        assert!(read_solution(&path).is_err());
+    }
+
+    #[test]
+    fn paths_out_of_the_data_dir_are_rejected() {
+        let dir = env::temp_dir().join("tdm-data");
+        assert_eq!(
+            resolve_in(&dir, Path::new("solutions/ships.json")),
+            Ok(dir.join("solutions").join("ships.json"))
+        );
+
+        for path in [
+            "",
+            "/etc/passwd",
+            "../ships.json",
+            "solutions/../../ships.json",
+            "./ships.json",
+        ] {
+            assert_eq!(
+                resolve_in(&dir, Path::new(path)),
+                Err(format!("Некоректний шлях до файлу розв'язку: {path}").into())
+            );
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use std::sync::{Arc, Mutex, RwLock};
 
+use nalgebra::DMatrix;
+use ratio_extension::{BigRationalExt, RatioExt};
+
 #[macro_export]
 macro_rules! ensure_eq {
     ($expr1: expr, $expr2: expr) => {
@@ -31,10 +34,138 @@ macro_rules! dbg_display {
     }};
 }
 
+/// `.par_iter()` with the `parallel` feature, `.iter()` without it
+#[macro_export]
+macro_rules! par_iter {
+    ($e: expr) => {{
+        #[cfg(feature = "parallel")]
+        let iter = $e.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let iter = $e.iter();
+        iter
+    }};
+}
+
+/// `.par_iter_mut()` with the `parallel` feature, `.iter_mut()` without it
+#[macro_export]
+macro_rules! par_iter_mut {
+    ($e: expr) => {{
+        #[cfg(feature = "parallel")]
+        let iter = $e.par_iter_mut();
+        #[cfg(not(feature = "parallel"))]
+        let iter = $e.iter_mut();
+        iter
+    }};
+}
+
+/// `.into_par_iter()` with the `parallel` feature, `.into_iter()` without it
+#[macro_export]
+macro_rules! into_par_iter {
+    ($e: expr) => {{
+        #[cfg(feature = "parallel")]
+        let iter = $e.into_par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let iter = $e.into_iter();
+        iter
+    }};
+}
+
+/// [`rayon::join`] with the `parallel` feature, sequential calls without it
+pub fn join<A, B, RA, RB>(oper_a: A, oper_b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    #[cfg(feature = "parallel")]
+    return rayon::join(oper_a, oper_b);
+    #[cfg(not(feature = "parallel"))]
+    return (oper_a(), oper_b());
+}
+
+/// Calls `f` with the index of every item, on the `rayon` pool, if `parallel` is set
+/// and the `parallel` feature is enabled, and on the calling thread otherwise
+pub fn for_each_mut<T, F>(items: &mut [T], parallel: bool, f: F)
+where
+    T: Send,
+    F: Fn(usize, &mut T) + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    if parallel {
+        use rayon::prelude::{
+            IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator,
+        };
+
+        items
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(i, item)| f(i, item));
+        return;
+    }
+    #[cfg(not(feature = "parallel"))]
+    let _ = parallel;
+    items
+        .iter_mut()
+        .enumerate()
+        .for_each(|(i, item)| f(i, item));
+}
+
 pub fn arc_mut<T>(value: T) -> Arc<Mutex<T>> {
     Arc::new(Mutex::new(value))
 }
 
 pub fn arc_rw<T>(value: T) -> Arc<RwLock<T>> {
     Arc::new(RwLock::new(value))
-}
\ No newline at end of file
+}
+
+/// Renders the matrix with every column right-aligned to its widest entry
+pub fn format_matrix(m: &DMatrix<BigRationalExt>) -> String {
+    let cells = m.map(|el| match el {
+        RatioExt::Inf => "∞".to_owned(),
+        RatioExt::MinusInf => "-∞".to_owned(),
+        RatioExt::Nan => "NaN".to_owned(),
+        RatioExt::Finite(ratio) => ratio.to_string(),
+    });
+    let widths = cells
+        .column_iter()
+        .map(|column| column.iter().map(|cell| cell.chars().count()).max())
+        .collect::<Vec<_>>();
+
+    cells
+        .row_iter()
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:>0$}", width.unwrap_or(0)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_str_eq;
+
+    use super::*;
+
+    #[test]
+    fn format_matrix_aligns_columns() {
+        let m = DMatrix::from_row_slice(
+            2,
+            3,
+            &[
+                BigRationalExt::from((1.into(), 3.into())),
+                BigRationalExt::from_float(100.),
+                BigRationalExt::MinusInf,
+                BigRationalExt::from_float(100.),
+                BigRationalExt::Inf,
+                BigRationalExt::from_float(-0.5),
+            ],
+        );
+
+        assert_str_eq!(format_matrix(&m), "1/3 100   -∞\n100   ∞ -1/2");
+    }
+}
@@ -3,17 +3,37 @@
     windows_subsystem = "windows"
 )]
 
+use std::env;
+
 use course_project::command;
+use log::LevelFilter;
 use tauri_plugin_log::{LogTarget, LoggerBuilder};
 
+/// Variable, that sets the verbosity of the log, e.g. `debug` to see every simplex iteration
+const LOG_LEVEL_VAR: &str = "TDM_LOG";
+
 fn main() {
     tauri::Builder::default()
         .plugin(
             LoggerBuilder::default()
                 .targets([LogTarget::LogDir, LogTarget::Stdout])
+                .level(log_level())
                 .build(),
         )
-        .invoke_handler(tauri::generate_handler![command::compute])
+        .invoke_handler(tauri::generate_handler![
+            command::compute,
+            command::cancel_compute,
+            command::save_solution,
+            command::load_solution
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+/// [`LevelFilter::Info`] by default, which keeps the solve start, end and incumbents only
+fn log_level() -> LevelFilter {
+    env::var(LOG_LEVEL_VAR)
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(LevelFilter::Info)
+}
@@ -9,12 +9,17 @@ use num_rational::BigRational;
 use num_traits::{One, Zero};
 use ratio_extension::{BigRationalExt, RatioExt};
 
+/// Number `big_part * M + small_part` of the Big-M method, where M is greater than any real number.
+///
+/// Only the objective coefficients carry M: the artificial variables cost `±M`, while the tableau and the rhs are real.
+/// So the solver multiplies a big number only by a real one: an estimation is the dot product of the basis coefficients
+/// by a tableau column or by the rhs, and [`Problem::scale_to_integer_coefficients`](super::Problem::scale_to_integer_coefficients)
+/// scales the coefficients by a real factor. The product of two big numbers would need the `M²` term,
+/// which is not represented, so [`Mul`] asserts in the debug builds, that one of the operands is real
 #[derive(
     Debug,
     Clone,
     Copy,
-    PartialEq,
-    PartialOrd,
     Default,
     derive_more::Add,
     derive_more::Sub,
@@ -87,6 +92,22 @@ impl<T: One + Zero> BigNumber<T> {
     }
 }
 
+impl PartialEq for BigNumber<f64> {
+    fn eq(&self, other: &Self) -> bool {
+        self.big_part == other.big_part && self.small_part == other.small_part
+    }
+}
+
+/// Orders by the big parts, and by the small parts if the big parts are equal
+impl PartialOrd for BigNumber<f64> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.big_part.partial_cmp(&other.big_part)? {
+            Ordering::Equal => self.small_part.partial_cmp(&other.small_part),
+            ord => Some(ord),
+        }
+    }
+}
+
 impl BigNumber<f64> {
     pub fn total_cmp(&self, other: &Self) -> Ordering {
         match self.big_part.total_cmp(&other.big_part) {
@@ -96,6 +117,51 @@ impl BigNumber<f64> {
     }
 }
 
+/// Tolerance-based checks for the float path, which accumulates rounding errors.
+///
+/// The exact [`BigRationalExt`] path should keep using the exact `is_zero`/`is_integer`
+pub trait NearlyIntegral {
+    fn is_near_zero(&self, eps: f64) -> bool;
+
+    fn is_near_integer(&self, eps: f64) -> bool;
+
+    /// Snaps the value within `eps` of an integer to that integer, and the one near zero to zero,
+    /// so that `2.9999999` is shown as `3`. Other values stay as they are
+    fn clean(&mut self, eps: f64);
+}
+
+impl NearlyIntegral for f64 {
+    fn is_near_zero(&self, eps: f64) -> bool {
+        self.abs() <= eps
+    }
+
+    fn is_near_integer(&self, eps: f64) -> bool {
+        (self - self.round()).is_near_zero(eps)
+    }
+
+    fn clean(&mut self, eps: f64) {
+        if self.is_near_integer(eps) {
+            // Adding zero turns `-0.0` into `0.0`
+            *self = self.round() + 0.0;
+        }
+    }
+}
+
+impl NearlyIntegral for BigNumber<f64> {
+    fn is_near_zero(&self, eps: f64) -> bool {
+        self.big_part.is_near_zero(eps) && self.small_part.is_near_zero(eps)
+    }
+
+    fn is_near_integer(&self, eps: f64) -> bool {
+        self.big_part.is_near_integer(eps) && self.small_part.is_near_integer(eps)
+    }
+
+    fn clean(&mut self, eps: f64) {
+        self.big_part.clean(eps);
+        self.small_part.clean(eps);
+    }
+}
+
 impl<T> BigNumber<RatioExt<T>>
 where
     T: Integer + Clone,
@@ -108,6 +174,60 @@ where
     }
 }
 
+/// Agrees with the [`Ord`], so unlike the parts themselves, `Nan` is equal to `Nan`
+impl PartialEq for BigNumber<BigRationalExt> {
+    fn eq(&self, other: &Self) -> bool {
+        self.total_cmp(other).is_eq()
+    }
+}
+
+impl Eq for BigNumber<BigRationalExt> {}
+
+impl PartialOrd for BigNumber<BigRationalExt> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Orders by the big parts, and by the small parts if the big parts are equal.
+///
+/// Matches the order of the parts wherever it is defined, while `Nan` is less than everything
+impl Ord for BigNumber<BigRationalExt> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.total_cmp(other)
+    }
+}
+
+/// Compares with a scalar as with the [`BigNumber`] of zero big part,
+/// so a nonzero big part always dominates the scalar
+impl PartialEq<BigRationalExt> for BigNumber<BigRationalExt> {
+    fn eq(&self, other: &BigRationalExt) -> bool {
+        self.big_part.is_zero() && &self.small_part == other
+    }
+}
+
+impl PartialEq<BigNumber<BigRationalExt>> for BigRationalExt {
+    fn eq(&self, other: &BigNumber<BigRationalExt>) -> bool {
+        other == self
+    }
+}
+
+/// Matches the [`PartialOrd`] of two [`BigNumber`]s, where the scalar has zero big part
+impl PartialOrd<BigRationalExt> for BigNumber<BigRationalExt> {
+    fn partial_cmp(&self, other: &BigRationalExt) -> Option<Ordering> {
+        match self.big_part.partial_cmp(&BigRationalExt::zero())? {
+            Ordering::Equal => self.small_part.partial_cmp(other),
+            ord => Some(ord),
+        }
+    }
+}
+
+impl PartialOrd<BigNumber<BigRationalExt>> for BigRationalExt {
+    fn partial_cmp(&self, other: &BigNumber<BigRationalExt>) -> Option<Ordering> {
+        other.partial_cmp(self).map(Ordering::reverse)
+    }
+}
+
 impl TryFrom<BigNumber<BigRational>> for BigRational {
     type Error = String;
 
@@ -299,14 +419,18 @@ where
     }
 }
 
-/// It won't be used for multiplication of two big numbers with big parts both
+/// The `M²` term of two big numbers is dropped, see [`BigNumber`] for why the solver never produces it
 impl<T> Mul for BigNumber<T>
 where
-    T: Add<Output = T> + Mul<Output = T> + Clone,
+    T: Add<Output = T> + Mul<Output = T> + Zero + Clone,
 {
     type Output = BigNumber<T>;
 
     fn mul(self, rhs: Self) -> Self::Output {
+        debug_assert!(
+            self.big_part.is_zero() || rhs.big_part.is_zero(),
+            "Multiplication of two big numbers, the M² term would be lost"
+        );
         // (ai + b) * (xi + y) = axii + ayi + bxi + by = axii + (ay + bx)i + by
         BigNumber {
             big_part: /* self.big_part * rhs.big_part */
@@ -320,20 +444,101 @@ where
 impl<'a, T: 'a> MulAssign for BigNumber<T>
 where
     Self: Clone,
-    T: Add<Output = T> + Mul<Output = T> + Clone,
+    T: Add<Output = T> + Mul<Output = T> + Zero + Clone,
 {
     fn mul_assign(&mut self, rhs: Self) {
         *self = self.clone() * rhs
     }
 }
 
+/// Defined only for divisors with zero big part, as division by an M-quantity is ill-defined
+impl<T> Div for BigNumber<T>
+where
+    T: Div<Output = T> + Zero + Clone,
+{
+    type Output = BigNumber<T>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        debug_assert!(rhs.big_part.is_zero(), "Division by a big number");
+        BigNumber {
+            big_part: self.big_part / rhs.small_part.clone(),
+            small_part: self.small_part / rhs.small_part,
+        }
+    }
+}
+
+impl<T> DivAssign for BigNumber<T>
+where
+    Self: Clone,
+    T: Div<Output = T> + Zero + Clone,
+{
+    fn div_assign(&mut self, rhs: Self) {
+        *self = self.clone() / rhs
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use proptest::{prop_assert_eq, proptest};
+    use proptest::{prelude::*, prop_assert_eq, proptest};
 
     use super::*;
 
+    fn big_rational_ext() -> impl Strategy<Value = BigRationalExt> {
+        prop_oneof![
+            Just(BigRationalExt::Inf),
+            Just(BigRationalExt::MinusInf),
+            Just(BigRationalExt::Nan),
+            (-1000i64..1000, 1i64..100)
+                .prop_map(|(numer, denom)| BigRationalExt::from((numer.into(), denom.into()))),
+        ]
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Multiplication of two big numbers")]
+    fn mul_of_two_big_numbers_panics() {
+        let _ = BigNumber::new(1., 2.) * BigNumber::new(-1., 3.);
+    }
+
+    #[test]
+    fn mul_by_a_real_number_keeps_the_big_part() {
+        assert_eq!(
+            BigNumber::new(1., 2.) * BigNumber::from(3.),
+            BigNumber::new(3., 6.)
+        );
+        assert_eq!(
+            BigNumber::from(-2.) * BigNumber::new(1., 2.),
+            BigNumber::new(-2., -4.)
+        );
+    }
+
     proptest! {
+        #[test]
+        fn ord(
+            big_part1 in big_rational_ext(),
+            small_part1 in big_rational_ext(),
+            big_part2 in big_rational_ext(),
+            small_part2 in big_rational_ext(),
+        ) {
+            let a = BigNumber::new(big_part1.clone(), small_part1.clone());
+            let b = BigNumber::new(big_part2.clone(), small_part2.clone());
+
+            if big_part1.total_cmp(&big_part2).is_ne() {
+                prop_assert_eq!(big_part1.total_cmp(&big_part2), a.cmp(&b))
+            } else {
+                prop_assert_eq!(small_part1.total_cmp(&small_part2), a.cmp(&b))
+            }
+            let parts_ordering = match big_part1.partial_cmp(&big_part2) {
+                Some(Ordering::Equal) => small_part1.partial_cmp(&small_part2),
+                ordering => ordering,
+            };
+            if let Some(ordering) = parts_ordering {
+                prop_assert_eq!(ordering, a.cmp(&b));
+            }
+            prop_assert_eq!(a == b, a.cmp(&b).is_eq());
+            prop_assert_eq!(a.partial_cmp(&b), Some(a.cmp(&b)));
+        }
+
         #[test]
         fn comparison(
             big_part1 in -1000.0..1000.0,
@@ -565,4 +770,107 @@ mod tests {
             )
         }
     }
+
+    proptest! {
+        #[test]
+        fn div_by_finite_big_number(
+            big_part in -1000.0..1000.0,
+            small_part in -1000.0..1000.0,
+            rhs in 1.0..1000.0,
+        ) {
+            let num = BigNumber::<f64> { big_part, small_part };
+
+            prop_assert_eq!(
+                num / BigNumber::from(rhs),
+                BigNumber::<f64> {
+                    big_part: big_part / rhs,
+                    small_part: small_part / rhs,
+                }
+            )
+        }
+    }
+
+    #[test]
+    fn div_rational_by_finite_big_number() {
+        let mut num = BigNumber::new(
+            BigRationalExt::from_float(3.),
+            BigRationalExt::from_float(-1.),
+        );
+        num /= BigNumber::from(BigRationalExt::from_float(2.));
+
+        assert_eq!(
+            num,
+            BigNumber::new(
+                BigRationalExt::from_float(1.5),
+                BigRationalExt::from_float(-0.5)
+            )
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Division by a big number")]
+    fn div_by_big_number_is_ill_defined() {
+        let _ = BigNumber::<f64>::from(1.) / BigNumber::one_big();
+    }
+
+    #[test]
+    fn near_integer() {
+        assert!(0.9999999.is_near_integer(1e-6));
+        assert!(!0.9999999.is_near_integer(1e-9));
+        assert!(!BigRational::from_float(0.9999999).unwrap().is_integer());
+
+        assert!((-1e-9).is_near_zero(1e-6));
+        assert!(!0.5.is_near_integer(1e-6));
+
+        let num = BigNumber::<f64> {
+            big_part: 1e-12,
+            small_part: 2.0000001,
+        };
+        assert!(num.is_near_integer(1e-6));
+        assert!(!num.is_near_zero(1e-6));
+    }
+
+    #[test]
+    fn clean_snaps_the_float_dust() {
+        let clean = |mut value: f64| {
+            value.clean(1e-6);
+            value
+        };
+        assert_eq!(clean(2.9999999), 3.);
+        assert_eq!(clean(-1e-9).to_bits(), 0f64.to_bits());
+        assert_eq!(clean(2.5), 2.5);
+        assert_eq!(clean(2.999), 2.999);
+
+        let mut num = BigNumber::<f64> {
+            big_part: 1e-12,
+            small_part: 2.0000001,
+        };
+        num.clean(1e-6);
+        assert_eq!(num, BigNumber::new(0., 2.));
+    }
+
+    #[test]
+    fn cmp_with_scalar() {
+        let ratio = |n: i64| BigRationalExt::from_integer(n.into());
+
+        assert!(BigNumber::one_big() > ratio(1_000_000));
+        assert!(ratio(1_000_000) < BigNumber::one_big());
+        assert!(-BigNumber::<BigRationalExt>::one_big() < ratio(-1_000_000));
+        assert!(BigNumber::new(ratio(0), ratio(3)) > ratio(2));
+        assert!(ratio(2) < BigNumber::new(ratio(0), ratio(3)));
+        assert_eq!(BigNumber::new(ratio(0), ratio(3)), ratio(3));
+        assert_ne!(BigNumber::new(ratio(1), ratio(3)), ratio(3));
+        assert_eq!(
+            BigNumber::new(BigRationalExt::Nan, ratio(0)).partial_cmp(&ratio(0)),
+            None
+        );
+
+        // Same as comparing with another big number
+        assert_eq!(
+            BigNumber::one_big().partial_cmp(&ratio(5)),
+            BigNumber::one_big().partial_cmp(&BigNumber::from(ratio(5)))
+        );
+        assert!(BigNumber::one_big() < BigNumber::new(ratio(1), ratio(1)));
+    }
 }
@@ -0,0 +1,61 @@
+use num_rational::BigRational;
+use ratio_extension::BigRationalExt;
+
+use super::{big_number::BigNumber, SolveWarning};
+
+/// Structured progress record emitted while solving
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveEvent {
+    /// A simplex iteration is about to run
+    IterationStarted {
+        n: u32,
+        estimation: BigNumber<BigRationalExt>,
+    },
+    /// Pivot element of the current iteration was selected
+    PivotChosen { row: usize, col: usize },
+    /// Branch-and-bound started to solve the branch at the `path`
+    BranchStarted { path: String },
+    /// Branch-and-bound saved a new integer solution.
+    /// It is the best one of its subtree, not necessarily of the whole problem
+    IncumbentFound {
+        objective: BigRational,
+        path: String,
+        vars: Vec<BigRational>,
+    },
+    /// Branch-and-bound discarded a branch, that can't improve the incumbent
+    BranchPruned { path: String },
+    /// Something about the solve looks fragile, the result is not affected
+    Warning(SolveWarning),
+}
+
+/// Receiver of [`SolveEvent`]s.
+///
+/// Has to be [`Sync`], because branch-and-bound reports from several threads at once.
+pub type EventSink<'a> = dyn Fn(SolveEvent) + Sync + 'a;
+
+/// Default sink, that writes events to the log.
+///
+/// Only the incumbents and the warnings are logged at `info` and above,
+/// every iteration and branch is logged at `debug`
+pub fn log_event(event: SolveEvent) {
+    match event {
+        SolveEvent::IterationStarted { n, estimation } => {
+            log::debug!("Iteration: {n}");
+            log::debug!("Function estimation: {estimation}");
+        }
+        SolveEvent::PivotChosen { row, col } => {
+            log::debug!("Pivot column: {col}");
+            log::debug!("Pivot row: {row}");
+        }
+        SolveEvent::BranchStarted { path } => log::debug!("{path}"),
+        SolveEvent::IncumbentFound {
+            objective, path, ..
+        } => {
+            log::info!("{path}. Branch all integers. Saving. Objective: {objective}")
+        }
+        SolveEvent::BranchPruned { path } => {
+            log::debug!("{path}. Branch worse than the best_sol. Returning.")
+        }
+        SolveEvent::Warning(warning) => log::warn!("{warning}"),
+    }
+}
@@ -1,8 +1,20 @@
 mod big_number;
+mod cancellation;
+mod event;
 mod problem;
+mod ship_model;
 mod solution;
+mod solver;
+mod stats;
 mod table;
+mod table128;
 
+pub use big_number::NearlyIntegral;
+pub use cancellation::*;
+pub use event::*;
 pub use problem::*;
+pub use ship_model::*;
 pub use solution::*;
+pub use solver::*;
+pub use stats::*;
 pub use table::*;
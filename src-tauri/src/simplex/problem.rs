@@ -1,24 +1,32 @@
+mod mps;
 #[cfg(test)]
 mod tests;
 
 use std::{
-    fmt, hint,
+    collections::VecDeque,
+    fmt,
     mem::{self, MaybeUninit},
     ops::{Add, Mul, MulAssign},
-    sync::{Arc, Mutex},
+    sync::{mpsc, Mutex},
     thread,
 };
 
 use derive_more::{Display, IsVariant};
 use derive_new::new;
-use nalgebra::{Const, DMatrix, DVector, Dynamic, RowDVector, Scalar, UninitMatrix};
-use num_traits::{One, Zero};
-use ratio_extension::BigRationalExt;
+use nalgebra::{Const, DMatrix, DVector, DVectorSlice, Dynamic, RowDVector, Scalar, UninitMatrix};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Signed, Zero};
+use ratio_extension::{lcm_denominators, BigRationalExt};
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-use crate::{helpers::arc_mut, simplex::SolutionError};
+use crate::{helpers, into_par_iter, par_iter, simplex::SolutionError};
 
-use super::{big_number::BigNumber, SimplexTable, Solution, SolutionResult};
+use super::{
+    big_number::BigNumber, is_unit_column, log_event, table128::SimplexTable128, CancellationToken,
+    EventSink, SimplexTable, Solution, SolutionResult, SolveEvent, SolveStats, SolveWarning,
+};
 
 #[derive(Debug, Clone, PartialEq, Display, new)]
 #[display(
@@ -43,13 +51,122 @@ pub struct Constraint {
     rhs: BigRationalExt,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, IsVariant)]
+impl Constraint {
+    pub fn coefficients(&self) -> &RowDVector<BigRationalExt> {
+        &self.coefficients
+    }
+
+    pub fn sign(&self) -> Sign {
+        self.sign
+    }
+
+    pub fn rhs(&self) -> &BigRationalExt {
+        &self.rhs
+    }
+
+    /// The same constraint with the nonnegative rhs, that the simplex method requires.
+    ///
+    /// A negative rhs is negated together with the coefficients, so the sign of an inequality is flipped,
+    /// while an equality stays an equality. Otherwise the constraint is returned as is
+    pub fn with_nonnegative_rhs(self) -> Constraint {
+        if self.rhs < BigRationalExt::zero() {
+            self * -BigRationalExt::one()
+        } else {
+            self
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Display, IsVariant)]
 pub enum Sign {
+    #[display(fmt = "<=")]
     Less = -1,
+    #[display(fmt = "=")]
     Equals = 0,
+    #[display(fmt = ">=")]
     Greater = 1,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, derive_more::Error, IsVariant)]
+pub enum ProblemError {
+    #[display(fmt = "Задача не містить змінних")]
+    NoVariables,
+    #[display(fmt = "Задача не містить обмежень")]
+    NoConstraints,
+    /// The columns of the `one_big` coefficients are the initial basis, one per constraint
+    #[display(fmt = "Початковий базис містить {found} змінних замість {expected}")]
+    BasisSize { expected: usize, found: usize },
+    /// The column of the initial basis is not the unit vector of its constraint
+    #[display(fmt = "Стовпець {column} початкового базису не є одиничним")]
+    NonIdentityBasis { column: usize },
+}
+
+/// Reason, why a [`Solution`] does not fit the [`Problem`], see [`Problem::verify`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstraintViolation {
+    /// [`Solution::vars`] has `found` variables instead of the `expected` original ones,
+    /// so it is a solution of another problem, and the rest is not checked
+    VariableCount { expected: usize, found: usize },
+    /// `lhs sign rhs` does not hold for the constraint in the `row`
+    Constraint {
+        row: usize,
+        lhs: BigRationalExt,
+        sign: Sign,
+        rhs: BigRationalExt,
+    },
+    /// The variable with the `index` is negative
+    NegativeVariable { index: usize },
+    /// [`Solution::fn_val`] is not the value of the objective function at [`Solution::vars`]
+    Objective {
+        expected: BigRationalExt,
+        actual: BigRationalExt,
+    },
+}
+
+/// Origin of a column in the normalized problem
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, IsVariant)]
+pub enum VariableRole {
+    /// Variable of the original problem
+    Original,
+    /// Compensating variable of an inequality constraint
+    Slack,
+    /// Artificial variable of the Big-M method
+    Artificial,
+}
+
+/// Value of M in the Big-M method
+#[derive(Debug, Clone, PartialEq)]
+pub enum BigM {
+    /// M is kept apart from the real numbers by [`BigNumber`], so it dominates any coefficient.
+    /// This is how the solver works
+    Symbolic,
+    /// M is a number, that has to exceed every objective coefficient by [`BIG_M_SAFETY_FACTOR`]
+    Numeric(BigRationalExt),
+}
+
+/// How many times a [`BigM::Numeric`] has to exceed the largest objective coefficient
+pub const BIG_M_SAFETY_FACTOR: u32 = 1000;
+
+/// Number of the entries of the normalized constraints, from which [`Problem::new`] normalizes them
+/// on the `rayon` pool. The smaller problems, like the ones of the UI, are normalized on the calling thread,
+/// as the overhead of the pool outweighs the work. The result is the same either way.
+/// Has no effect without the `parallel` feature
+pub const PARALLEL_NORMALIZE_THRESHOLD: usize = 10_000;
+
+/// Integers of the fractions, that the tableau is solved with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NumericBackend {
+    /// Exact and unbounded, but slow
+    #[default]
+    BigInt,
+    /// Much faster, but the values have to fit into `i128` at every step.
+    ///
+    /// Every operation is checked, and on the overflow the solve starts over with [`NumericBackend::BigInt`],
+    /// so the result is always the same, as of the `BigInt` solve. The `i128` solve reports no [`SolveEvent`]s,
+    /// only the fallback one does
+    I128,
+}
+
 #[derive(Debug, Clone, PartialEq, Display)]
 #[display(
     fmt = "Problem: {{\n    objective_function: {},\n    constraints:\n{}\n    rhs:\n{}\n}}",
@@ -61,185 +178,1167 @@ pub struct Problem {
     pub(crate) objective_function: ObjectiveFunction<BigNumber<BigRationalExt>>,
     pub(crate) constraints: DMatrix<BigRationalExt>,
     pub(crate) rhs: DVector<BigRationalExt>,
+    pub(crate) n_slack_variables: usize,
+    pub(crate) n_artificial_variables: usize,
+    pub(crate) lexicographic_ratio_test: bool,
+    max_iterations: Option<u32>,
+    cancellation: Option<CancellationToken>,
+    numeric_backend: NumericBackend,
+    /// Factor, that the objective function was multiplied by in [`Problem::scale_to_integer_coefficients`]
+    pub(crate) objective_scale: BigRational,
+    pub(crate) presolved: Presolved,
+}
+
+/// What [`Problem::presolve`] took out of the problem and [`Problem::equilibrate`] changed in it,
+/// to be undone in every solution
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Presolved {
+    /// Indices and values of the fixed original variables
+    fixed: Vec<(usize, BigRational)>,
+    /// Part of the minimized `fn_val`, that the fixed variables make up
+    objective: BigRational,
+    /// Factors, that the columns of the original variables were multiplied by, empty if not equilibrated
+    column_scales: Vec<BigRational>,
+}
+
+impl Presolved {
+    /// Puts the fixed variables and their part of the objective function back into the `solution`,
+    /// then brings the variables back to their original units
+    pub(crate) fn restore(&self, mut solution: Solution) -> Solution {
+        for (j, value) in &self.fixed {
+            solution.vars[*j] = value.clone();
+        }
+        for (var, scale) in solution.vars.iter_mut().zip(&self.column_scales) {
+            *var *= scale;
+        }
+        solution.fn_val += &self.objective;
+        solution
+    }
+
+    /// Factor, that the column of the original variable `j` was multiplied by
+    fn column_scale(&self, j: usize) -> BigRational {
+        self.column_scales
+            .get(j)
+            .cloned()
+            .unwrap_or_else(BigRational::one)
+    }
 }
 
 impl Problem {
+    /// The objective function and the constraints may have different numbers of coefficients.
+    /// Shorter rows are padded with zeros on the right to the widest one,
+    /// so the coefficients keep their variable indices
     pub fn new(
         objective_function: ObjectiveFunction<BigRationalExt>,
         constraints: Vec<Constraint>,
-    ) -> Self {
-        Self::normalize(objective_function, constraints)
+    ) -> Result<Self, ProblemError> {
+        if objective_function.coefficients.is_empty()
+            && constraints
+                .iter()
+                .all(|constraint| constraint.coefficients.is_empty())
+        {
+            return Err(ProblemError::NoVariables);
+        }
+        if constraints.is_empty() {
+            return Err(ProblemError::NoConstraints);
+        }
+        Ok(Self::normalize(objective_function, constraints))
+    }
+
+    /// Same as [`Problem::new`], but states the problem with plain vectors.
+    ///
+    /// Every constraint is a tuple of its coefficients, sign and rhs
+    pub fn from_rows(
+        objective: Vec<BigRationalExt>,
+        minimize: bool,
+        constraints: Vec<(Vec<BigRationalExt>, Sign, BigRationalExt)>,
+    ) -> Result<Self, ProblemError> {
+        Self::new(
+            ObjectiveFunction::new(RowDVector::from_vec(objective), minimize),
+            constraints
+                .into_iter()
+                .map(|(coefficients, sign, rhs)| {
+                    Constraint::new(RowDVector::from_vec(coefficients), sign, rhs)
+                })
+                .collect(),
+        )
+    }
+
+    /// The largest absolute value of the objective function coefficients of the original variables
+    pub fn max_objective_coefficient(&self) -> BigRationalExt {
+        self.objective_function
+            .coefficients
+            .columns(0, self.n_original())
+            .iter()
+            .map(|coefficient| {
+                let coefficient = coefficient.small_part();
+                if coefficient < &BigRationalExt::zero() {
+                    -coefficient.clone()
+                } else {
+                    coefficient.clone()
+                }
+            })
+            .max_by(BigRationalExt::total_cmp)
+            .unwrap_or_else(Zero::zero)
+    }
+
+    /// Warns, if the `big_m` doesn't exceed the [`Problem::max_objective_coefficient`]
+    /// by [`BIG_M_SAFETY_FACTOR`] times, so it may not dominate the objective function.
+    ///
+    /// Is a no-op for the [`BigM::Symbolic`], which the solver uses
+    pub fn check_big_m(&self, big_m: &BigM) -> Option<SolveWarning> {
+        let BigM::Numeric(big_m) = big_m else {
+            return None;
+        };
+        let max_coefficient = self.max_objective_coefficient();
+        let bound = &max_coefficient * &BigRationalExt::from_integer(BIG_M_SAFETY_FACTOR.into());
+        (big_m <= &bound).then(|| SolveWarning::BigMTooSmall {
+            big_m: big_m.clone(),
+            max_coefficient,
+        })
+    }
+
+    /// Problem of `n_vars` variables with zero objective function and without constraints.
+    ///
+    /// Is meant to be built up with [`Problem::set_objective`] and [`Problem::add_constraint`]
+    ///
+    /// # Panics
+    /// If `n_vars` is zero
+    pub fn empty(n_vars: usize, minimize: bool) -> Self {
+        Self::normalize(
+            ObjectiveFunction::new(RowDVector::zeros(n_vars), minimize),
+            Vec::new(),
+        )
+    }
+
+    /// Replaces the coefficients of the original variables in the objective function.
+    /// Shorter coefficients are padded with zeros
+    ///
+    /// # Panics
+    /// If there are more coefficients, than there are original variables
+    pub fn set_objective(&mut self, coefficients: RowDVector<BigRationalExt>) {
+        let n_original = self.n_original();
+        assert!(
+            coefficients.len() <= n_original,
+            "The objective function has more coefficients, than there are variables"
+        );
+        self.objective_function
+            .coefficients
+            .columns_mut(0, n_original)
+            .iter_mut()
+            .enumerate()
+            .for_each(|(j, coefficient)| {
+                *coefficient = coefficients
+                    .get(j)
+                    .cloned()
+                    .map_or_else(Zero::zero, BigNumber::from)
+            });
+    }
+
+    /// Number of variables of the original problem.
+    /// They occupy the first columns of the normalized problem
+    pub fn n_original(&self) -> usize {
+        self.objective_function.n_significant_variables
+    }
+
+    /// Number of compensating variables, that follow the original ones
+    pub fn n_slack(&self) -> usize {
+        self.n_slack_variables
+    }
+
+    /// Number of artificial variables, that occupy the last columns
+    pub fn n_artificial(&self) -> usize {
+        self.n_artificial_variables
+    }
+
+    /// Number of the rows of the normalized problem
+    pub fn num_constraints(&self) -> usize {
+        self.constraints.nrows()
+    }
+
+    /// Number of the columns of the normalized problem: original, compensating and artificial variables
+    pub fn num_variables(&self) -> usize {
+        self.constraints.ncols()
+    }
+
+    /// One-line overview, unlike the [`Display`](fmt::Display), that prints all the coefficients
+    pub fn summary(&self) -> String {
+        format!(
+            "{}, {} vars ({} slack, {} artificial), {} constraints",
+            if self.objective_function.minimization {
+                "min"
+            } else {
+                "max"
+            },
+            self.n_original(),
+            self.n_slack(),
+            self.n_artificial(),
+            self.num_constraints(),
+        )
+    }
+
+    /// Role of the `i`-th column of the normalized problem.
+    /// Returns [`None`] if `i` is out of bounds
+    pub fn variable_role(&self, i: usize) -> Option<VariableRole> {
+        let n_original = self.n_original();
+        let n_slack = self.n_slack();
+        if i < n_original {
+            Some(VariableRole::Original)
+        } else if i < n_original + n_slack {
+            Some(VariableRole::Slack)
+        } else if i < n_original + n_slack + self.n_artificial() {
+            Some(VariableRole::Artificial)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the problems are the same up to the order of the compensating and the artificial variables.
+    ///
+    /// The original variables, the rows and the rhs have to match exactly, while every other column
+    /// of `self` has to match a distinct column of `other` of the same role, together with its objective coefficient.
+    /// The solver settings, like [`Problem::with_max_iterations`], are not compared
+    pub fn equivalent_to(&self, other: &Problem) -> bool {
+        let n_original = self.n_original();
+        if self.objective_function.minimization != other.objective_function.minimization
+            || self.objective_scale != other.objective_scale
+            || self.presolved != other.presolved
+            || n_original != other.n_original()
+            || self.n_slack() != other.n_slack()
+            || self.n_artificial() != other.n_artificial()
+            || self.constraints.shape() != other.constraints.shape()
+            || self.rhs != other.rhs
+            || self.objective_function.coefficients.columns(0, n_original)
+                != other.objective_function.coefficients.columns(0, n_original)
+            || self.constraints.columns(0, n_original) != other.constraints.columns(0, n_original)
+        {
+            return false;
+        }
+
+        fn column(
+            problem: &Problem,
+            j: usize,
+        ) -> (
+            Option<VariableRole>,
+            &BigNumber<BigRationalExt>,
+            DVectorSlice<'_, BigRationalExt>,
+        ) {
+            (
+                problem.variable_role(j),
+                &problem.objective_function.coefficients[j],
+                problem.constraints.column(j),
+            )
+        }
+        let mut matched = vec![false; other.num_variables()];
+        (n_original..self.num_variables()).all(|j| {
+            let j_column = column(self, j);
+            (n_original..other.num_variables())
+                .find(|k| !matched[*k] && column(other, *k) == j_column)
+                .map(|k| matched[k] = true)
+                .is_some()
+        })
+    }
+
+    /// Breaks ties of the minimum ratio test lexicographically instead of taking the first row.
+    ///
+    /// Guarantees, that degenerate problems do not cycle. Disabled by default
+    pub fn with_lexicographic_ratio_test(mut self, enabled: bool) -> Self {
+        self.lexicographic_ratio_test = enabled;
+        self
+    }
+
+    /// Stops every simplex run after `max_iterations` with [`SolutionError::IterationLimit`].
+    /// Unlimited by default
+    pub fn with_max_iterations(mut self, max_iterations: u32) -> Self {
+        self.max_iterations = Some(max_iterations);
+        self
+    }
+
+    /// Stops the solve with [`SolutionError::Cancelled`] before the next iteration,
+    /// once the `token` is cancelled. The branches of [`Problem::solve_with_whole`] share it
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Solves the tableau with the given [`NumericBackend`], including every branch of [`Problem::solve_with_whole`].
+    /// [`NumericBackend::BigInt`] by default
+    pub fn with_numeric_backend(mut self, backend: NumericBackend) -> Self {
+        self.numeric_backend = backend;
+        self
+    }
+
+    /// Multiplies every constraint by the least common multiple of the denominators of its coefficients and rhs,
+    /// and the objective function by the one of its coefficients, so that the solver works with integers.
+    ///
+    /// The artificial variables are not scaled, so they stay the initial basis.
+    /// Neither the feasible region, nor the optimal variables change,
+    /// and the `fn_val` of the following solves is divided by the objective function scale back
+    pub fn scale_to_integer_coefficients(&mut self) {
+        let n_scaled = self.constraints.ncols() - self.n_artificial();
+        for (mut row, rhs) in self.constraints.row_iter_mut().zip(self.rhs.iter_mut()) {
+            let scale = BigRationalExt::from_integer(denominators_lcm(
+                row.columns(0, n_scaled).iter().chain([&*rhs]),
+            ));
+            row.columns_mut(0, n_scaled)
+                .iter_mut()
+                .for_each(|el| *el *= &scale);
+            *rhs *= &scale;
+        }
+
+        let n_original = self.n_original();
+        let coefficients = self.objective_function.coefficients.columns(0, n_original);
+        let scale = denominators_lcm(coefficients.iter().map(BigNumber::small_part));
+        self.objective_function
+            .coefficients
+            .columns_mut(0, n_original)
+            .iter_mut()
+            .for_each(|coefficient| {
+                *coefficient *= BigNumber::from(BigRationalExt::from_integer(scale.clone()))
+            });
+        self.objective_scale *= BigRational::from_integer(scale);
+    }
+
+    /// Scales the column of every original variable, so that its largest absolute coefficient in the constraints is one.
+    /// Keeps the fractions smaller during the solve, when the magnitudes of the coefficients are spread.
+    ///
+    /// Multiplying the column `j` by `scale_j` stands for the variable `x_j / scale_j`, so the objective function
+    /// is scaled together with the column, and the optimum doesn't change. The following solves report
+    /// the variables in their original units. Columns without nonzero coefficients are not scaled.
+    /// Returns the factors, that the columns were multiplied by.
+    /// Has to be called after the last [`Problem::add_constraint`], as the new rows would miss the scales
+    pub fn equilibrate(&mut self) -> Vec<BigRationalExt> {
+        let n_original = self.n_original();
+        self.presolved
+            .column_scales
+            .resize(n_original, BigRational::one());
+        (0..n_original)
+            .map(|j| {
+                let largest = self
+                    .constraints
+                    .column(j)
+                    .iter()
+                    .map(|el| {
+                        BigRational::try_from(el.clone())
+                            .expect("Constraints have finite coefficients")
+                            .abs()
+                    })
+                    .max()
+                    .unwrap_or_else(BigRational::zero);
+                if largest.is_zero() {
+                    return BigRationalExt::one();
+                }
+                let scale = largest.recip();
+                let ext_scale = BigRationalExt::from(scale.clone());
+                self.constraints
+                    .column_mut(j)
+                    .iter_mut()
+                    .for_each(|el| *el *= &ext_scale);
+                self.objective_function.coefficients[j] *= BigNumber::from(ext_scale.clone());
+                self.presolved.column_scales[j] *= scale;
+                ext_scale
+            })
+            .collect()
+    }
+
+    /// Removes the artificial variables of the constraints, that already have the unit column
+    /// of an original or a compensating variable, like the ones of the `<=` constraints.
+    /// [`SimplexTable`] takes those columns into the initial basis instead.
+    ///
+    /// The optimum stays the same, while the tableau gets smaller.
+    /// [`Problem::scale_to_integer_coefficients`] has to be called before, as it doesn't keep the unit columns
+    pub fn remove_redundant_artificials(&mut self) {
+        let first_artificial = self.constraints.ncols() - self.n_artificial();
+        let redundant = (first_artificial..self.constraints.ncols())
+            .filter(|artificial| {
+                let Some(k) = self
+                    .constraints
+                    .column(*artificial)
+                    .iter()
+                    .position(|el| !el.is_zero())
+                else {
+                    return false;
+                };
+                (0..first_artificial).any(|j| is_unit_column(self.constraints.column(j), k))
+            })
+            .collect::<Vec<_>>();
+        for artificial in redundant.into_iter().rev() {
+            self.remove_variable(artificial);
+        }
+    }
+
+    /// Fixes the original variables, that an equality with a single nonzero coefficient forces to a value.
+    ///
+    /// The value is substituted into the other rows, and the equality is removed together with its artificial variable.
+    /// The column of the fixed variable is zeroed instead of being removed, so the variables keep their indices,
+    /// and the following solves report the fixed value together with its part of the objective function.
+    /// Repeats, while the substitution leaves new such equalities.
+    ///
+    /// An equality stays, if it forces a negative value, which is up to the solver to reject,
+    /// or if the substitution would make the rhs of another row negative.
+    /// Has to be called after the last [`Problem::add_constraint`], as the new rows would miss the fixed variables
+    pub fn presolve(&mut self) {
+        while let Some((row, j, value)) = (0..self.num_constraints())
+            .find_map(|row| self.forced_variable(row).map(|(j, value)| (row, j, value)))
+        {
+            log::debug!("Presolve fixes x{} = {value}", j + 1);
+            let ext_value = BigRationalExt::from(value.clone());
+            for r in (0..self.num_constraints()).filter(|r| r != &row) {
+                self.rhs[r] = &self.rhs[r] - &(&self.constraints[(r, j)] * &ext_value);
+            }
+            self.constraints.column_mut(j).fill(Zero::zero());
+
+            let coefficient = mem::replace(
+                &mut self.objective_function.coefficients[j],
+                BigNumber::zero(),
+            );
+            let coefficient = BigRational::try_from(coefficient.small_part().clone())
+                .expect("Original variables have finite coefficients");
+            let objective = coefficient * &value / &self.objective_scale;
+            // `fn_val` is minimized, so it is negated for the maximization
+            self.presolved.objective += if self.objective_function.minimization {
+                objective
+            } else {
+                -objective
+            };
+            self.presolved.fixed.push((j, value));
+
+            let first_artificial = self.constraints.ncols() - self.n_artificial();
+            let artificial = (first_artificial..self.constraints.ncols())
+                .find(|k| !self.constraints[(row, *k)].is_zero());
+            self.constraints =
+                mem::replace(&mut self.constraints, DMatrix::zeros(0, 0)).remove_row(row);
+            self.rhs = mem::replace(&mut self.rhs, DVector::zeros(0)).remove_row(row);
+            if let Some(artificial) = artificial {
+                self.remove_variable(artificial);
+            }
+        }
+    }
+
+    /// The original variable and its value, that the equality in the `row` forces,
+    /// if [`Problem::presolve`] may fix it
+    fn forced_variable(&self, row: usize) -> Option<(usize, BigRational)> {
+        if self.constraint_sign(row) != Sign::Equals {
+            return None;
+        }
+        let mut nonzero = self
+            .constraints
+            .row(row)
+            .columns(0, self.n_original())
+            .iter()
+            .enumerate()
+            .filter(|(_, coefficient)| !coefficient.is_zero())
+            .map(|(j, coefficient)| (j, coefficient.clone()))
+            .collect::<Vec<_>>();
+        let (j, coefficient) = nonzero.pop()?;
+        if !nonzero.is_empty() {
+            return None;
+        }
+        let value = &self.rhs[row] / &coefficient;
+        if value.is_negative() {
+            return None;
+        }
+        let keeps_rhs = (0..self.num_constraints())
+            .filter(|r| r != &row)
+            .all(|r| !(&self.rhs[r] - &(&self.constraints[(r, j)] * &value)).is_negative());
+        if !keeps_rhs {
+            return None;
+        }
+        Some((j, BigRational::try_from(value).ok()?))
     }
 
     pub fn solve(self) -> SolutionResult {
-        let mut table = SimplexTable::new(self);
+        self.solve_observed(&log_event)
+    }
+
+    /// Same as [`Problem::solve`], but reports progress to the `sink` instead of the log
+    pub fn solve_observed(self, sink: &EventSink<'_>) -> SolutionResult {
+        log::debug!("Solving {}", self.summary());
+        if let Some(result) = self.solve_i128() {
+            return result;
+        }
+        let max_iterations = self.max_iterations;
+        let cancellation = self.cancellation.clone();
+        let objective_scale = self.objective_scale.clone();
+        let presolved = self.presolved.clone();
+        let mut table = SimplexTable::new(self)?;
+        Self::solve_table(
+            &mut table,
+            max_iterations,
+            cancellation.as_ref(),
+            &objective_scale,
+            &presolved,
+            sink,
+        )
+    }
 
-        log::info!("Iteration: 1");
-        log::info!("Function estimation: {}", table.function_estimation());
-        let (mut solution, mut prev_pivot_col) = table.step(None);
+    /// Same as [`Problem::solve`], but keeps the problem.
+    ///
+    /// Unlike `problem.clone().solve()`, copies only the tableau, the rhs and the objective function,
+    /// see [`SimplexTable::from_ref`]
+    pub fn solve_ref(&self) -> SolutionResult {
+        self.solve_ref_observed(&log_event)
+    }
+
+    /// Same as [`Problem::solve_ref`], but reports progress to the `sink` instead of the log
+    pub fn solve_ref_observed(&self, sink: &EventSink<'_>) -> SolutionResult {
+        log::debug!("Solving {}", self.summary());
+        if let Some(result) = self.solve_i128() {
+            return result;
+        }
+        Self::solve_table(
+            &mut SimplexTable::from_ref(self)?,
+            self.max_iterations,
+            self.cancellation.as_ref(),
+            &self.objective_scale,
+            &self.presolved,
+            sink,
+        )
+    }
+
+    /// Result of the [`NumericBackend::I128`] solve, or [`None`], if the backend is not chosen, or the solve overflowed
+    fn solve_i128(&self) -> Option<SolutionResult> {
+        if self.numeric_backend != NumericBackend::I128 {
+            return None;
+        }
+        let result = SimplexTable128::from_problem(self)
+            .and_then(|table| table.solve(self.max_iterations, self.cancellation.as_ref()));
+        if result.is_none() {
+            log::debug!("The i128 solve overflowed, falling back to BigInt");
+        }
+        Some(result?.map(|solution| {
+            self.presolved.restore(Solution {
+                fn_val: solution.fn_val / &self.objective_scale,
+                ..solution
+            })
+        }))
+    }
+
+    /// Steps the `table` of the problem with the given settings to the end
+    fn solve_table(
+        table: &mut SimplexTable,
+        max_iterations: Option<u32>,
+        cancellation: Option<&CancellationToken>,
+        objective_scale: &BigRational,
+        presolved: &Presolved,
+        sink: &EventSink<'_>,
+    ) -> SolutionResult {
+        let may_start = |n| {
+            if matches!(cancellation, Some(token) if token.is_cancelled()) {
+                return Err(SolutionError::Cancelled);
+            }
+            if matches!(max_iterations, Some(max) if n > max) {
+                return Err(SolutionError::IterationLimit);
+            }
+            Ok(())
+        };
+        may_start(1)?;
+        sink(SolveEvent::IterationStarted {
+            n: 1,
+            estimation: table.function_estimation(),
+        });
+        let (mut solution, mut prev_pivot_col) = table.step(None, sink);
         let mut iteration = 2u32;
 
         while solution.is_none() {
-            log::info!("Iteration: {iteration}");
-            log::info!("Function estimation: {}", table.function_estimation());
-            (solution, prev_pivot_col) = table.step(prev_pivot_col);
+            may_start(iteration)?;
+            sink(SolveEvent::IterationStarted {
+                n: iteration,
+                estimation: table.function_estimation(),
+            });
+            (solution, prev_pivot_col) = table.step(prev_pivot_col, sink);
             iteration += 1;
         }
 
-        solution.unwrap()
+        solution.unwrap().map(|solution| {
+            presolved.restore(Solution {
+                fn_val: solution.fn_val / objective_scale,
+                ..solution
+            })
+        })
     }
 
-    pub fn solve_with_whole(self) -> SolutionResult {
-        let solution = self.clone().solve()?;
+    /// Distinct optimal vertices, at most `max_optima` of them, starting from the one of [`Problem::solve`].
+    ///
+    /// The optimum is not unique, if a non-basic variable has the zero estimation at the optimal table,
+    /// as it enters the basis without changing the objective function, see [`SimplexTable::alternative_optima`].
+    /// The vertices are searched breadth-first over such pivots. The optimal set may be unbounded,
+    /// though the optimum is finite, unlike the [`SolutionError::Infinite`]. Then only its vertices are returned.
+    /// Always solves with [`NumericBackend::BigInt`]
+    pub fn solve_all_optima(self, max_optima: usize) -> Result<Vec<Solution>, SolutionError> {
+        fn sorted_basis(table: &SimplexTable) -> Vec<usize> {
+            let mut basis = table.basis().iter().copied().collect::<Vec<_>>();
+            basis.sort_unstable();
+            basis
+        }
 
-        let progress = "root";
-        log::info!("{progress}");
+        log::debug!("Solving {} for all optima", self.summary());
+        let mut table = SimplexTable::from_ref(&self)?;
+        let optimum = Self::solve_table(
+            &mut table,
+            self.max_iterations,
+            self.cancellation.as_ref(),
+            &self.objective_scale,
+            &self.presolved,
+            &log_event,
+        )?;
+
+        let mut optima = vec![optimum];
+        let mut visited = vec![sorted_basis(&table)];
+        let mut queue = VecDeque::from([table]);
+        'search: while let Some(table) = queue.pop_front() {
+            for mut alternative in table.alternative_optima() {
+                if optima.len() >= max_optima {
+                    break 'search;
+                }
+                let basis = sorted_basis(&alternative);
+                if visited.contains(&basis) {
+                    continue;
+                }
+                visited.push(basis);
+                // The alternative table is optimal already, so it stops at once
+                let (Some(solution), _) = alternative.step(None, &log_event) else {
+                    continue;
+                };
+                let solution = solution?;
+                let solution = self.presolved.restore(Solution {
+                    fn_val: solution.fn_val / &self.objective_scale,
+                    ..solution
+                });
+                // A degenerate vertex has several bases
+                if !optima.iter().any(|optimum| optimum.vars == solution.vars) {
+                    optima.push(solution);
+                }
+                queue.push_back(alternative);
+            }
+        }
+        optima.truncate(max_optima);
+        Ok(optima)
+    }
+
+    /// Same as [`Problem::solve_ref`], but also returns the basic variables of the optimal table
+    /// together with their [`VariableRole`]s, one for every constraint, in the order of the rows.
+    ///
+    /// The indices are the ones of the columns of the normalized problem, see [`Problem::variable_role`].
+    /// Always solves with [`NumericBackend::BigInt`]
+    pub fn solve_with_basis(
+        &self,
+    ) -> Result<(Solution, Vec<(usize, VariableRole)>), SolutionError> {
+        log::debug!("Solving {} with the basis", self.summary());
+        let mut table = SimplexTable::from_ref(self)?;
+        let solution = Self::solve_table(
+            &mut table,
+            self.max_iterations,
+            self.cancellation.as_ref(),
+            &self.objective_scale,
+            &self.presolved,
+            &log_event,
+        )?;
+        let basis = table
+            .basis()
+            .iter()
+            .map(|i| {
+                let role = self
+                    .variable_role(*i)
+                    .expect("Basis has the columns of the problem");
+                (*i, role)
+            })
+            .collect();
+        Ok((solution, basis))
+    }
+
+    /// Same as [`Problem::solve`], but also returns the [`SolveStats`]
+    pub fn solve_with_stats(self) -> (SolutionResult, SolveStats) {
+        collect_stats(|sink| self.solve_observed(sink))
+    }
+
+    /// Any basic feasible solution of the original variables, without optimizing the objective function.
+    ///
+    /// Runs phase one: only the sum of the artificial variables is minimized.
+    /// Returns `None`, if the problem is infeasible, or the iterations limit is reached
+    pub fn feasible_point(&self) -> Option<Vec<BigRationalExt>> {
+        let mut phase_one = self.clone();
+        phase_one.objective_function.minimization = true;
+        phase_one
+            .objective_function
+            .coefficients
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, coefficient)| {
+                *coefficient = if matches!(self.variable_role(i), Some(VariableRole::Artificial)) {
+                    BigNumber::one_big()
+                } else {
+                    BigNumber::zero()
+                };
+            });
+
+        // Positive artificial variables leave the big part in the objective, which is reported as infinite
+        let Solution { vars, .. } = phase_one.solve().ok()?;
+        Some(vars.into_iter().map(BigRationalExt::from).collect())
+    }
+
+    /// Whether the constraints have a solution, see [`Problem::feasible_point`]
+    pub fn is_feasible(&self) -> bool {
+        self.feasible_point().is_some()
+    }
 
-        self.improve(solution, progress)
+    /// Whether the objective function can't improve without a limit, that is the problem is infeasible,
+    /// or has no feasible direction `d`, along which the objective function improves.
+    ///
+    /// Instead of the full solve, the objective function is optimized over the directions:
+    /// the constraints with the zero rhs and `sum d <= 1`, which is bounded, and is zero without such a direction.
+    /// Returns `true`, if either of the solves fails, for example on the iterations limit
+    pub fn is_bounded(&self) -> bool {
+        if !self.is_feasible() {
+            return true;
+        }
+        let n_original = self.n_original();
+        let mut constraints = (0..self.num_constraints())
+            .map(|row| {
+                Constraint::new(
+                    self.constraints
+                        .row(row)
+                        .columns(0, n_original)
+                        .into_owned(),
+                    self.constraint_sign(row),
+                    BigRationalExt::zero(),
+                )
+            })
+            .collect::<Vec<_>>();
+        constraints.push(Constraint::new(
+            RowDVector::from_element(n_original, BigRationalExt::one()),
+            Sign::Less,
+            BigRationalExt::one(),
+        ));
+        let objective = ObjectiveFunction::new(
+            self.objective_function
+                .coefficients
+                .columns(0, n_original)
+                .map(|coefficient| coefficient.small_part().clone()),
+            self.objective_function.minimization,
+        );
+        let mut directions = Problem::normalize(objective, constraints);
+        directions.max_iterations = self.max_iterations;
+        directions.cancellation = self.cancellation.clone();
+        // `fn_val` is minimized, so an improving direction makes it negative
+        !matches!(directions.solve(), Ok(solution) if solution.fn_val.is_negative())
     }
 
-    fn improve(self, solution: Solution, progress: &str) -> SolutionResult {
-        let solution = Arc::new(solution);
-        log::info!("Solution:\n{solution}");
+    /// Checks the `solution` exactly against the constraints and the objective function.
+    ///
+    /// The constraints are recovered from the normalized problem: the sign of a row is given by its slack variable.
+    /// Rows with negative rhs were negated during the normalization, which does not change their meaning
+    pub fn verify(&self, solution: &Solution) -> Result<(), Vec<ConstraintViolation>> {
+        let n_original = self.n_original();
+        if solution.vars.len() != n_original {
+            return Err(vec![ConstraintViolation::VariableCount {
+                expected: n_original,
+                found: solution.vars.len(),
+            }]);
+        }
+        let vars = solution
+            .vars
+            .iter()
+            .cloned()
+            .map(BigRationalExt::from)
+            .collect::<Vec<_>>();
+        let mut violations = vars
+            .iter()
+            .enumerate()
+            .filter(|(_, var)| var < &&BigRationalExt::zero())
+            .map(|(index, _)| ConstraintViolation::NegativeVariable { index })
+            .collect::<Vec<_>>();
 
-        let Solution { vars, .. } = &*solution;
+        // The columns may be equilibrated
+        let scaled_vars = self.scaled_point(&vars);
+        for (row, (constraint, rhs)) in self.constraints.row_iter().zip(&self.rhs).enumerate() {
+            let lhs = constraint
+                .columns(0, n_original)
+                .iter()
+                .zip(&scaled_vars)
+                .map(|(coefficient, var)| coefficient * var)
+                .sum::<BigRationalExt>();
+            let sign = self.constraint_sign(row);
+            let satisfied = match sign {
+                Sign::Less => &lhs <= rhs,
+                Sign::Equals => &lhs == rhs,
+                Sign::Greater => &lhs >= rhs,
+            };
+            if !satisfied {
+                violations.push(ConstraintViolation::Constraint {
+                    row,
+                    lhs,
+                    sign,
+                    rhs: rhs.clone(),
+                });
+            }
+        }
 
-        let Some((i, var)) = vars.par_iter().enumerate().find_map_any(|(i, var)| {
-            (!var.is_integer()).then_some((i, var))
-        }) else {
-            log::info!("Solution has all integer variables. Returning.");
-            return Ok(Arc::try_unwrap(solution).unwrap());
+        let objective = self.objective_value_at(&vars);
+        // `fn_val` is minimized, so it is negated for the maximization
+        let expected = if self.objective_function.minimization {
+            objective
+        } else {
+            -objective
         };
-        log::info!("Solution has non-integer variables");
+        let actual = BigRationalExt::from(solution.fn_val.clone());
+        if expected != actual {
+            violations.push(ConstraintViolation::Objective { expected, actual });
+        }
 
-        let whole_part: BigRationalExt = var.trunc().into();
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Value of the objective function in the sense of the problem at the `point` of the original variables,
+    /// without solving. Includes the part of the variables, that [`Problem::presolve`] fixed,
+    /// and is not affected by [`Problem::scale_to_integer_coefficients`] and [`Problem::equilibrate`]
+    ///
+    /// # Panics
+    /// If the `point` has not as many values, as there are original variables
+    pub fn objective_value_at(&self, point: &[BigRationalExt]) -> BigRationalExt {
+        let n_original = self.n_original();
+        assert_eq!(point.len(), n_original, "Wrong number of variables");
+        let objective = self
+            .objective_function
+            .coefficients
+            .columns(0, n_original)
+            .iter()
+            .zip(&self.scaled_point(point))
+            .map(|(coefficient, var)| {
+                &BigRationalExt::try_from(coefficient.clone())
+                    .expect("Original variables have no big coefficients")
+                    * var
+            })
+            .sum::<BigRationalExt>()
+            / BigRationalExt::from(self.objective_scale.clone());
+        // The fixed part is minimized, like `fn_val`
+        let fixed = BigRationalExt::from(self.presolved.objective.clone());
+        if self.objective_function.minimization {
+            objective + fixed
+        } else {
+            objective - fixed
+        }
+    }
+
+    /// The `point` of the original variables in the units of the equilibrated columns
+    fn scaled_point(&self, point: &[BigRationalExt]) -> Vec<BigRationalExt> {
+        point
+            .iter()
+            .enumerate()
+            .map(|(j, var)| var / &BigRationalExt::from(self.presolved.column_scale(j)))
+            .collect()
+    }
+
+    /// The dual of the original problem, before the normalization.
+    ///
+    /// Every original constraint gets a nonnegative dual variable in the order of the constraints,
+    /// and an equality gets two of them, `y+` and `y-`, as its dual variable is free.
+    /// For the minimization the constraints are turned into `>=`, and the dual is
+    /// the maximization of `b^T y` with `A^T y <= c`.
+    /// For the maximization they are turned into `<=`, and the dual is
+    /// the minimization of `b^T y` with `A^T y >= c`.
+    /// So both problems have the same optimal objective value
+    pub fn dual(&self) -> Problem {
+        let n_original = self.n_original();
         let minimization = self.objective_function.minimization;
+        // Columns of A^T and the coefficients of b^T y
+        let mut columns = Vec::with_capacity(self.constraints.nrows());
+        let mut objective = Vec::with_capacity(self.constraints.nrows());
+        for (row, rhs) in self.rhs.iter().enumerate() {
+            let coefficients = self.constraints.row(row).columns(0, n_original).transpose();
+            match (self.constraint_sign(row), minimization) {
+                (Sign::Greater, true) | (Sign::Less, false) => {
+                    columns.push(coefficients);
+                    objective.push(rhs.clone());
+                }
+                (Sign::Less, true) | (Sign::Greater, false) => {
+                    columns.push(-coefficients);
+                    objective.push(-rhs.clone());
+                }
+                (Sign::Equals, _) => {
+                    columns.push(coefficients.clone());
+                    objective.push(rhs.clone());
+                    columns.push(-coefficients);
+                    objective.push(-rhs.clone());
+                }
+            }
+        }
+        let transposed = DMatrix::from_columns(&columns);
 
-        let best_sol = arc_mut::<Option<Solution>>(None);
-
-        // Parallel branches computation
-        thread::scope(|s| -> Result<(), SolutionError> {
-            let best_sol = best_sol.clone();
-            let problem = Arc::new(&self);
-
-            // Left branch
-            let left_join_handle = s.spawn({
-                let problem = problem.clone();
-                let best_sol = best_sol.clone();
-                let whole_part = whole_part.clone();
-                move || -> Result<(), SolutionError> {
-                    Self::add_branch(
-                        &format!("{progress}.left"),
-                        &problem,
-                        i,
-                        Sign::Less,
-                        whole_part,
-                        best_sol,
-                        minimization,
+        Problem::new(
+            ObjectiveFunction::new(RowDVector::from_vec(objective), !minimization),
+            transposed
+                .row_iter()
+                .zip(self.objective_function.coefficients.iter())
+                .map(|(coefficients, c)| {
+                    Constraint::new(
+                        coefficients.into_owned(),
+                        if minimization {
+                            Sign::Less
+                        } else {
+                            Sign::Greater
+                        },
+                        BigRationalExt::try_from(c.clone())
+                            .expect("Original variables have no big coefficients"),
                     )
+                })
+                .collect(),
+        )
+        .expect("The dual has a variable for every constraint and a constraint for every variable")
+    }
+
+    /// Sign of the original constraint in the `row`, given by its slack variable
+    fn constraint_sign(&self, row: usize) -> Sign {
+        match self
+            .constraints
+            .row(row)
+            .columns(self.n_original(), self.n_slack())
+            .iter()
+            .find(|coefficient| !coefficient.is_zero())
+        {
+            Some(slack) if slack > &BigRationalExt::zero() => Sign::Less,
+            Some(_) => Sign::Greater,
+            None => Sign::Equals,
+        }
+    }
+
+    pub fn solve_with_whole(self) -> SolutionResult {
+        self.solve_with_whole_observed(&log_event)
+    }
+
+    /// Same as [`Problem::solve_with_whole`], but reports progress to the `sink` instead of the log
+    pub fn solve_with_whole_observed(self, sink: &EventSink<'_>) -> SolutionResult {
+        // Branching can't move a variable, that the presolve fixed
+        if self
+            .presolved
+            .fixed
+            .iter()
+            .any(|(j, value)| !(value * self.presolved.column_scale(*j)).is_integer())
+        {
+            return Err(SolutionError::Absent);
+        }
+        let solution = self.solve_ref_observed(sink)?;
+
+        let progress = "root";
+        log::debug!("{progress}");
+
+        // The relaxation is often integral already, then there is nothing to branch on
+        if solution.vars.iter().all(|var| var.is_integer()) {
+            log::debug!("Relaxation has all integer variables. Returning.");
+            return Ok(solution);
+        }
+
+        self.improve(solution, progress, sink)
+    }
+
+    /// Integer solutions in the order, in which branch-and-bound improves on them, the last one is the optimum.
+    ///
+    /// The solve runs on its own thread, while the branches report their incumbents over a channel.
+    /// Only the ones, that are better than every previous one, are yielded, as the branches know only their own subtree.
+    /// If the problem has no integer solution or the solve fails, the iterator ends early,
+    /// use [`Problem::solve_with_whole`] to get the error. Dropping the iterator does not stop the solve,
+    /// cancel it with [`Problem::with_cancellation`] instead
+    pub fn solve_with_whole_iter(self) -> impl Iterator<Item = Solution> {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            // The check and the send are under the same lock, so the yielded solutions are ordered
+            let incumbent = Mutex::new((None::<Solution>, sender));
+            let offer = |solution: Solution| {
+                let (best, sender) = &mut *incumbent.lock().unwrap();
+                let is_improvement = match best {
+                    None => true,
+                    Some(best) => solution.is_better_than(best),
+                };
+                if is_improvement {
+                    *best = Some(solution.clone());
+                    // The receiver may be dropped, then the solution is not needed
+                    let _ = sender.send(solution);
                 }
+            };
+            let result = self.solve_with_whole_observed(&|event| {
+                if let SolveEvent::IncumbentFound {
+                    objective, vars, ..
+                } = &event
+                {
+                    offer(Solution {
+                        fn_val: objective.clone(),
+                        vars: vars.clone(),
+                    });
+                }
+                log_event(event);
             });
+            // An integral relaxation is returned without branching, so there is no incumbent to report
+            if let Ok(solution) = result {
+                offer(solution);
+            }
+        });
+        receiver.into_iter()
+    }
+
+    /// Same as [`Problem::solve_with_whole`], but also returns the [`SolveStats`] of all branches
+    pub fn solve_with_whole_and_stats(self) -> (SolutionResult, SolveStats) {
+        collect_stats(|sink| self.solve_with_whole_observed(sink))
+    }
+
+    fn improve(self, solution: Solution, progress: &str, sink: &EventSink<'_>) -> SolutionResult {
+        log::debug!("Solution:\n{solution}");
+
+        let Solution { vars, .. } = &solution;
 
-            // Right branch
-            let right_join_handle = s.spawn(move || -> Result<(), SolutionError> {
+        // The lowest fractional index, so that the branching does not depend on scheduling
+        let Some((i, var)) = vars.iter().enumerate().find(|(_, var)| !var.is_integer()) else {
+            log::debug!("Solution has all integer variables. Returning.");
+            return Ok(solution);
+        };
+        log::debug!("Solution has non-integer variables");
+
+        let whole_part: BigRationalExt = var.trunc().into();
+
+        let best_sol = Mutex::new(None);
+
+        // Parallel branches computation.
+        // With the `parallel` feature the branches run on the global `rayon` pool,
+        // so the number of concurrently solved branches is bounded by the available parallelism
+        let (left, right) = helpers::join(
+            || {
+                Self::add_branch(
+                    &format!("{progress}.left"),
+                    &self,
+                    i,
+                    Sign::Less,
+                    whole_part.clone(),
+                    &best_sol,
+                    sink,
+                )
+            },
+            || {
                 Self::add_branch(
                     &format!("{progress}.right"),
-                    &problem,
+                    &self,
                     i,
                     Sign::Greater,
-                    whole_part + BigRationalExt::one(),
-                    best_sol,
-                    minimization,
+                    &whole_part + &BigRationalExt::one(),
+                    &best_sol,
+                    sink,
                 )
-            });
-
-            left_join_handle.join().unwrap()?;
-            right_join_handle.join().unwrap()?;
+            },
+        );
+        left?;
+        right?;
 
-            Ok(())
-        })?;
-
-        log::info!("Computed both branches");
+        log::debug!("Computed both branches");
 
-        Arc::try_unwrap(best_sol)
-            .unwrap()
-            .into_inner()
-            .unwrap()
-            .ok_or(SolutionError::Absent)
+        best_sol.into_inner().unwrap().ok_or(SolutionError::Absent)
     }
 
+    /// Solves the branch and offers its integer solution as the new incumbent.
+    ///
+    /// The `best_sol` lock is never held while branching further: a worker, that waits
+    /// inside of a nested `rayon::join`, may pick up the sibling branch, which needs the lock
     fn add_branch(
         progress: &str,
         problem: &Problem,
         i: usize,
         constraint_sign: Sign,
         rhs: BigRationalExt,
-        best_sol: Arc<Mutex<Option<Solution>>>,
-        minimization: bool,
+        best_sol: &Mutex<Option<Solution>>,
+        sink: &EventSink<'_>,
     ) -> Result<(), SolutionError> {
-        log::info!("{progress}");
-        let mut problem = (*problem).clone();
+        sink(SolveEvent::BranchStarted {
+            path: progress.to_owned(),
+        });
+        log::debug!("{progress}: x{} {constraint_sign} {rhs}", i + 1);
+        // The sibling branch may be solved concurrently from the same `problem`,
+        // so the constraint can't be added in place and removed afterwards
+        let mut problem = problem.clone();
         problem.add_constraint_on_var(i, constraint_sign, rhs);
-        let branch_sol = problem.clone().solve()?;
-        let mut best_sol = best_sol.lock().unwrap();
-        match (&*best_sol, &branch_sol) {
-            (None, Solution { vars, .. }) => {
-                if vars.par_iter().all(|var| var.is_integer()) {
-                    log::info!("{progress}. Branch all integers. Saving.");
-                    *best_sol = Some(branch_sol);
-                    return Ok(());
-                }
-                log::info!("{progress}. Branch could be improved. Branching.");
+        let branch_sol = problem.solve_ref_observed(sink)?;
 
-                let Ok(improved_sol) = problem.improve(branch_sol, progress) else {
-                    return Ok(());
-                };
-                *best_sol = Some(improved_sol);
+        if let Some(Solution {
+            fn_val: best_fn_val,
+            ..
+        }) = &*best_sol.lock().unwrap()
+        {
+            let branch_fn_val = &branch_sol.fn_val;
+            // Equal objectives are not pruned, as the branch may hold a preferred tie
+            if best_fn_val < branch_fn_val {
+                sink(SolveEvent::BranchPruned {
+                    path: progress.to_owned(),
+                });
+                return Ok(());
             }
-            (
-                Some(Solution {
-                    fn_val: best_fn_val,
-                    ..
-                }),
-                Solution {
-                    fn_val: branch_fn_val,
-                    vars: branch_vars,
-                },
-            ) => {
-                if minimization && best_fn_val <= branch_fn_val {
-                    log::info!("{progress}. Branch worse than the best_sol. Returning.");
-                    return Ok(());
-                } else if !minimization && best_fn_val >= branch_fn_val {
-                    log::info!("{progress}. Branch worse than the best_sol. Returning.");
-                    return Ok(());
-                }
-                if branch_vars.par_iter().all(|var| var.is_integer()) {
-                    log::info!("{progress}. Branch all integers. Saving.");
-                    *best_sol = Some(branch_sol);
-                    return Ok(());
-                }
-                log::info!("{progress}. Branch could be improved. Branching.");
-                let Ok(maybe_improved_sol) = problem.improve(branch_sol, progress) else {
-                    return Ok(());
-                };
-                if matches!(
-                    &maybe_improved_sol,
-                    Solution { fn_val, vars }
-                    if ((minimization && fn_val < best_fn_val) || (!minimization && fn_val > best_fn_val))
-                    && vars.par_iter().all(|var| var.is_integer())
-                ) {
-                    *best_sol = Some(maybe_improved_sol);
-                }
+        }
+
+        let candidate = if par_iter!(branch_sol.vars).all(|var| var.is_integer()) {
+            branch_sol
+        } else {
+            log::debug!("{progress}. Branch could be improved. Branching.");
+            match problem.improve(branch_sol, progress, sink) {
+                Ok(improved_sol) => improved_sol,
+                Err(SolutionError::Cancelled) => return Err(SolutionError::Cancelled),
+                Err(_) => return Ok(()),
             }
+        };
+
+        let mut best_sol = best_sol.lock().unwrap();
+        let is_improvement = match &*best_sol {
+            None => true,
+            Some(best_sol) => candidate.is_better_than(best_sol),
+        };
+        if is_improvement {
+            sink(SolveEvent::IncumbentFound {
+                objective: candidate.fn_val.clone(),
+                path: progress.to_owned(),
+                vars: candidate.vars.clone(),
+            });
+            *best_sol = Some(candidate);
         }
         Ok(())
     }
 
     fn add_constraint_on_var(&mut self, i: usize, mut sign: Sign, rhs: BigRationalExt) {
-        if sign == Sign::Less && rhs == Zero::zero() {
+        // The variable is nonnegative anyway
+        if sign == Sign::Less && rhs.is_zero() {
             sign = Sign::Equals;
         }
-        let sign = sign;
+        let mut coefficients = RowDVector::zeros(i + 1);
+        coefficients[i] = One::one();
+        // The bound is in the original units, while the column may be equilibrated
+        let rhs = rhs / BigRationalExt::from(self.presolved.column_scale(i));
+        self.add_constraint(Constraint::new(coefficients, sign, rhs));
+    }
+
+    /// Same as [`Problem::add_constraint`] for every of the `constraints` in their order,
+    /// so that a generated batch is added fluently, see also the [`Extend`] implementation
+    ///
+    /// # Panics
+    /// If a constraint has more coefficients, than there are original variables
+    pub fn with_constraints(mut self, constraints: impl IntoIterator<Item = Constraint>) -> Self {
+        self.extend(constraints);
+        self
+    }
+
+    /// Appends the `constraint` on the original variables together with its compensating and artificial variables.
+    ///
+    /// Shorter coefficients are padded with zeros, like in [`Problem::new`]
+    ///
+    /// # Panics
+    /// If the constraint has more coefficients, than there are original variables
+    pub fn add_constraint(&mut self, constraint: Constraint) {
+        assert!(
+            constraint.coefficients.len() <= self.n_original(),
+            "The constraint has more coefficients, than there are variables"
+        );
+        let Constraint {
+            coefficients: original,
+            sign,
+            rhs,
+        } = constraint.with_nonnegative_rhs();
+        let original = |j: usize| original.get(j).cloned().unwrap_or_else(Zero::zero);
 
         let n_coefs = self.objective_function.coefficients.ncols();
         let n_constr = self.constraints.nrows();
@@ -255,9 +1354,8 @@ impl Problem {
                     Vec::with_capacity(n_coefs + 1);
                 coefficients.resize_with(n_coefs + 1, MaybeUninit::uninit);
 
-                coefficients[i].write(One::one());
-                for j in (0..n_coefs + 1).filter(|j| j != &i && j != &n_coefs) {
-                    coefficients[j].write(Zero::zero());
+                for (j, coefficient) in coefficients.iter_mut().take(n_coefs).enumerate() {
+                    coefficient.write(original(j));
                 }
 
                 // Artificial var
@@ -275,6 +1373,7 @@ impl Problem {
                     } else {
                         -BigNumber::one_big()
                     }]);
+                self.n_artificial_variables += 1;
             }
             sign @ Sign::Less | sign @ Sign::Greater => {
                 self.constraints = mem::replace(&mut self.constraints, DMatrix::zeros(0, 0))
@@ -284,19 +1383,16 @@ impl Problem {
                     Vec::with_capacity(n_coefs + 2);
                 coefficients.resize_with(n_coefs + 2, MaybeUninit::uninit);
 
-                coefficients[i].write(One::one());
-                for j in (0..n_coefs + 2)
-                    .filter(|j| j != &i && j != &n_significant && j != &(n_coefs + 1))
-                {
-                    coefficients[j].write(Zero::zero());
+                for j in (0..n_coefs + 1).filter(|j| j != &n_significant) {
+                    coefficients[j].write(original(j));
                 }
 
                 // Helper var
-                coefficients[n_significant].write(dbg!(match sign {
-                    Sign::Less => BigRationalExt::one(),
-                    Sign::Greater => -BigRationalExt::one(),
-                    _ => unsafe { hint::unreachable_unchecked() },
-                }));
+                coefficients[n_significant].write(if sign.is_less() {
+                    BigRationalExt::one()
+                } else {
+                    -BigRationalExt::one()
+                });
 
                 // Artificial var
                 coefficients[n_coefs + 1].write(BigRationalExt::one());
@@ -319,18 +1415,79 @@ impl Problem {
                     } else {
                         -BigNumber::one_big()
                     }]);
+                self.n_slack_variables += 1;
+                self.n_artificial_variables += 1;
             }
         }
         self.rhs.extend([rhs]);
     }
 
+    /// Removes the last constraint together with its compensating and artificial variables.
+    /// The artificial variable may be already removed, like by [`Problem::remove_redundant_artificials`].
+    ///
+    /// Undoes [`Problem::add_constraint`]
+    pub fn remove_last_constraint(&mut self) {
+        let row = self.constraints.nrows() - 1;
+        let n_significant = self.objective_function.n_significant_variables;
+
+        // Artificial var
+        let first_artificial = self.constraints.ncols() - self.n_artificial();
+        if let Some(j) = (first_artificial..self.constraints.ncols())
+            .find(|j| !self.constraints[(row, *j)].is_zero())
+        {
+            self.remove_variable(j);
+        }
+        // Helper var
+        if let Some(j) = (n_significant..n_significant + self.n_slack_variables)
+            .find(|j| !self.constraints[(row, *j)].is_zero())
+        {
+            self.remove_variable(j);
+        }
+
+        self.constraints =
+            mem::replace(&mut self.constraints, DMatrix::zeros(0, 0)).remove_row(row);
+        self.rhs = mem::replace(&mut self.rhs, DVector::zeros(0)).remove_row(row);
+    }
+
+    /// Removes the `i`-th column from the constraints and the objective function
+    pub fn remove_variable(&mut self, i: usize) {
+        match self.variable_role(i) {
+            Some(VariableRole::Original) => self.objective_function.n_significant_variables -= 1,
+            Some(VariableRole::Slack) => self.n_slack_variables -= 1,
+            Some(VariableRole::Artificial) => self.n_artificial_variables -= 1,
+            None => panic!("Variable {i} is out of bounds"),
+        }
+
+        self.constraints =
+            mem::replace(&mut self.constraints, DMatrix::zeros(0, 0)).remove_column(i);
+        self.objective_function.coefficients = mem::replace(
+            &mut self.objective_function.coefficients,
+            RowDVector::zeros(0),
+        )
+        .remove_column(i);
+    }
+
     #[inline]
     fn normalize(
+        objective_function: ObjectiveFunction<BigRationalExt>,
+        constraints: Vec<Constraint>,
+    ) -> Self {
+        Self::normalize_with_threshold(
+            objective_function,
+            constraints,
+            PARALLEL_NORMALIZE_THRESHOLD,
+        )
+    }
+
+    /// Same as [`Problem::normalize`], but on the `rayon` pool from the `parallel_threshold`
+    /// number of the entries instead of the [`PARALLEL_NORMALIZE_THRESHOLD`]
+    fn normalize_with_threshold(
         mut objective_function: ObjectiveFunction<BigRationalExt>,
         mut constraints: Vec<Constraint>,
+        parallel_threshold: usize,
     ) -> Self {
         let max_coefficients_count = constraints
-            .par_iter()
+            .iter()
             .map(|constraint| constraint.coefficients.len())
             .chain([objective_function.coefficients.len()])
             .max()
@@ -338,81 +1495,75 @@ impl Problem {
 
         assert_ne!(max_coefficients_count, 0);
 
-        constraints
-            .par_iter_mut()
-            // Reverse sign on constraints with negative rhs
-            .map(|constraint| {
-                if constraint.rhs < Zero::zero() {
-                    *constraint *= -BigRationalExt::one();
-                }
-                &mut constraint.coefficients
-            })
-            // Add zero coefficients to the constraints and objective function
-            .chain([&mut objective_function.coefficients])
-            .for_each(|coefficients| {
-                let current_len = coefficients.len();
-                for _ in 0..max_coefficients_count - current_len {
-                    let new_len = coefficients.len();
-                    *coefficients = coefficients.clone().insert_column(new_len, Zero::zero());
-                }
-            });
+        let n_slack_variables = constraints
+            .iter()
+            .filter(|constraint| !constraint.sign.is_equals())
+            .count();
+        let width = max_coefficients_count + n_slack_variables;
+        let nrows = constraints.len();
+        let parallel = nrows * (width + nrows) >= parallel_threshold;
 
-        // Inserting compensating variables
-        let non_equals = constraints
-            .par_iter_mut()
-            .enumerate()
-            .filter_map(|(i, constraint)| (!constraint.sign.is_equals()).then_some(i))
-            .collect::<Vec<_>>();
-        for i in non_equals {
-            let constraint = &mut constraints[i];
+        helpers::for_each_mut(&mut constraints, parallel, |_, constraint| {
+            // Coefficients and rhs are negated together, so an equality stays an equality
+            if constraint.rhs < BigRationalExt::zero() {
+                *constraint *= -BigRationalExt::one();
+            }
+            // Add zero coefficients to the constraints and objective function,
+            // including the ones of the compensating variables
             constraint
                 .coefficients
-                .extend([if constraint.sign.is_less() {
+                .resize_horizontally_mut(width, Zero::zero());
+        });
+        objective_function
+            .coefficients
+            .resize_horizontally_mut(width, Zero::zero());
+
+        // Inserting compensating variables
+        constraints
+            .iter_mut()
+            .filter(|constraint| !constraint.sign.is_equals())
+            .zip(max_coefficients_count..)
+            .for_each(|(constraint, j)| {
+                constraint.coefficients[j] = if constraint.sign.is_less() {
                     One::one()
                 } else {
                     -BigRationalExt::one()
-                }]);
-            objective_function
-                .coefficients
-                .extend([BigRationalExt::zero()]);
-            let constraints_count = constraints.len();
-            for i in (0..constraints_count).filter(|j| j != &i) {
-                constraints[i].coefficients.extend([BigRationalExt::zero()]);
-            }
-        }
+                };
+            });
 
         // Inserting artificial variables
         let is_minimization = objective_function.minimization;
         let mut objective_function: ObjectiveFunction<BigNumber<BigRationalExt>> =
             ObjectiveFunction {
-                n_significant_variables: objective_function.n_significant_variables,
+                // Zero coefficients of the original problem are significant variables too
+                n_significant_variables: max_coefficients_count,
                 coefficients: objective_function.coefficients.map(BigNumber::from),
                 minimization: is_minimization,
             };
-        for i in 0..constraints.len() {
-            constraints
-                .par_iter_mut()
-                .enumerate()
-                .for_each(|(j, constraint)| {
-                    constraint.coefficients.extend([if i != j {
-                        BigRationalExt::zero()
-                    } else {
-                        BigRationalExt::one()
-                    }]);
-                });
-            objective_function.coefficients.extend([if is_minimization {
+        helpers::for_each_mut(&mut constraints, parallel, |i, constraint| {
+            constraint.coefficients.extend((0..nrows).map(|j| {
+                if i != j {
+                    BigRationalExt::zero()
+                } else {
+                    BigRationalExt::one()
+                }
+            }));
+        });
+        objective_function.coefficients.extend((0..nrows).map(|_| {
+            if is_minimization {
                 // big_coefficient
                 BigNumber::one_big()
             } else {
                 // -big_coefficient
                 -BigNumber::one_big()
-            }])
-        }
+            }
+        }));
 
         // Reformat
         let (constraints, rhs) = {
             let nrows = constraints.len();
-            let ncols = constraints[0].coefficients.len();
+            // Original, compensating and artificial variables. There may be no constraints to count them in
+            let ncols = max_coefficients_count + n_slack_variables + nrows;
             let (constrains, rhs): (
                 DMatrix<MaybeUninit<BigRationalExt>>,
                 DVector<MaybeUninit<BigRationalExt>>,
@@ -444,13 +1595,47 @@ impl Problem {
         };
 
         Self {
+            n_artificial_variables: constraints.nrows(),
             objective_function,
             constraints,
             rhs,
+            n_slack_variables,
+            lexicographic_ratio_test: false,
+            max_iterations: None,
+            cancellation: None,
+            numeric_backend: NumericBackend::BigInt,
+            objective_scale: BigRational::one(),
+            presolved: Presolved::default(),
+        }
+    }
+}
+
+/// Adds the constraints one by one with [`Problem::add_constraint`]
+impl Extend<Constraint> for Problem {
+    fn extend<I: IntoIterator<Item = Constraint>>(&mut self, constraints: I) {
+        for constraint in constraints {
+            self.add_constraint(constraint);
         }
     }
 }
 
+/// Least common multiple of the denominators of the finite `values`
+fn denominators_lcm<'a>(values: impl IntoIterator<Item = &'a BigRationalExt>) -> BigInt {
+    lcm_denominators(&values.into_iter().cloned().collect::<Vec<_>>()).unwrap_or_else(BigInt::one)
+}
+
+/// Runs `solve` with a sink, that logs the events and accumulates the [`SolveStats`]
+fn collect_stats(
+    solve: impl FnOnce(&EventSink<'_>) -> SolutionResult,
+) -> (SolutionResult, SolveStats) {
+    let stats = Mutex::new(SolveStats::default());
+    let solution = solve(&|event| {
+        stats.lock().unwrap().record(&event);
+        log_event(event);
+    });
+    (solution, stats.into_inner().unwrap())
+}
+
 fn insert_row<T>(matrix: &mut DMatrix<T>, i: usize, values: Vec<T>)
 where
     T: Clone + Scalar + Default + Zero,
@@ -466,7 +1651,7 @@ where
     T: Send,
     MaybeUninit<T>: Sized,
 {
-    vec.into_par_iter()
+    into_par_iter!(vec)
         .map(|el| unsafe { el.assume_init() })
         .collect()
 }
@@ -475,7 +1660,7 @@ impl Mul<BigRationalExt> for Sign {
     type Output = Self;
 
     fn mul(self, rhs: BigRationalExt) -> Self::Output {
-        if rhs >= Zero::zero() {
+        if rhs >= BigRationalExt::zero() {
             return self;
         }
         match self {
@@ -492,6 +1677,41 @@ impl MulAssign<BigRationalExt> for Sign {
     }
 }
 
+/// Renders the constraint as `3x1 + 1/2x2 - x4 <= 5`, skipping zero coefficients
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut is_first = true;
+        for (i, coefficient) in self
+            .coefficients
+            .iter()
+            .enumerate()
+            .filter(|(_, coefficient)| !coefficient.is_zero())
+        {
+            let is_negative = coefficient < &BigRationalExt::zero();
+            match (is_first, is_negative) {
+                (true, false) => {}
+                (true, true) => write!(f, "-")?,
+                (false, false) => write!(f, " + ")?,
+                (false, true) => write!(f, " - ")?,
+            }
+            let abs = if is_negative {
+                -coefficient.clone()
+            } else {
+                coefficient.clone()
+            };
+            if !abs.is_one() {
+                write!(f, "{abs}")?;
+            }
+            write!(f, "x{}", i + 1)?;
+            is_first = false;
+        }
+        if is_first {
+            write!(f, "0")?;
+        }
+        write!(f, " {} {}", self.sign, self.rhs)
+    }
+}
+
 impl Mul<BigRationalExt> for Constraint {
     type Output = Self;
 
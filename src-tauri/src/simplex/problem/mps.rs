@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+
+use nalgebra::RowDVector;
+use num_traits::{One, Zero};
+use ratio_extension::BigRationalExt;
+
+use super::{Constraint, ObjectiveFunction, Problem, Sign};
+
+/// Section of the MPS file, that the following lines belong to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Name,
+    ObjSense,
+    Rows,
+    Columns,
+    Rhs,
+    Ranges,
+    Bounds,
+}
+
+/// Row of the MPS file: the objective function, or a constraint with its position in `rows`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row {
+    Objective,
+    /// The other `N` rows are free, so they are skipped
+    Free,
+    Constraint(usize),
+}
+
+struct MpsConstraint {
+    coefficients: Vec<BigRationalExt>,
+    sign: Sign,
+    rhs: BigRationalExt,
+    range: Option<BigRationalExt>,
+}
+
+impl Problem {
+    /// Parses the fixed or the free MPS format, where the names are separated by whitespace.
+    ///
+    /// The `RANGES` of a row and the `UP`, `LO`, `FX`, `BV` bounds of a column become extra constraints,
+    /// as every variable is nonnegative. `FR` and `MI` bounds and negative lower bounds are rejected.
+    /// Integer markers are skipped, call [`Problem::solve_with_whole`] for the integer problems
+    pub fn from_mps_str(s: &str) -> Result<Problem, String> {
+        let mut section = None;
+        let mut minimize = true;
+        let mut rows = HashMap::new();
+        let mut constraints: Vec<MpsConstraint> = Vec::new();
+        let mut columns = HashMap::new();
+        let mut objective: Vec<BigRationalExt> = Vec::new();
+        let mut bounds = Vec::new();
+
+        for (n, line) in s.lines().enumerate().map(|(n, line)| (n + 1, line)) {
+            if line.trim().is_empty() || line.starts_with('*') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            if !line.starts_with([' ', '\t']) {
+                let header = fields.next().unwrap();
+                section = Some(match header {
+                    "NAME" => Section::Name,
+                    "OBJSENSE" => {
+                        if let Some(sense) = fields.next() {
+                            minimize = parse_sense(sense, n)?;
+                        }
+                        Section::ObjSense
+                    }
+                    "ROWS" => Section::Rows,
+                    "COLUMNS" => Section::Columns,
+                    "RHS" => Section::Rhs,
+                    "RANGES" => Section::Ranges,
+                    "BOUNDS" => Section::Bounds,
+                    "ENDATA" => break,
+                    _ => return Err(format!("Рядок {n}: невідома секція {header:?}")),
+                });
+                continue;
+            }
+            let fields = fields.collect::<Vec<_>>();
+            match section {
+                None | Some(Section::Name) => {
+                    return Err(format!("Рядок {n}: дані поза секцією"));
+                }
+                Some(Section::ObjSense) => minimize = parse_sense(fields[0], n)?,
+                Some(Section::Rows) => {
+                    let [row_type, name] = fields[..] else {
+                        return Err(format!("Рядок {n}: очікується тип та назва рядка"));
+                    };
+                    let row = match row_type {
+                        "N" if !rows.values().any(|row| row == &Row::Objective) => Row::Objective,
+                        "N" => Row::Free,
+                        "L" | "G" | "E" => {
+                            constraints.push(MpsConstraint {
+                                coefficients: Vec::new(),
+                                sign: match row_type {
+                                    "L" => Sign::Less,
+                                    "G" => Sign::Greater,
+                                    _ => Sign::Equals,
+                                },
+                                rhs: Zero::zero(),
+                                range: None,
+                            });
+                            Row::Constraint(constraints.len() - 1)
+                        }
+                        _ => return Err(format!("Рядок {n}: невідомий тип рядка {row_type:?}")),
+                    };
+                    rows.insert(name, row);
+                }
+                Some(Section::Columns) => {
+                    // Integrality is up to the caller
+                    if fields.contains(&"'MARKER'") {
+                        continue;
+                    }
+                    let Some((column, entries)) = fields.split_first() else {
+                        continue;
+                    };
+                    let next_column = columns.len();
+                    let j = *columns.entry(*column).or_insert(next_column);
+                    for (row, value) in pairs(entries, n)? {
+                        let value = parse_number(value, n)?;
+                        let coefficients = match find_row(&rows, row, n)? {
+                            Row::Objective => &mut objective,
+                            Row::Free => continue,
+                            Row::Constraint(i) => &mut constraints[i].coefficients,
+                        };
+                        if coefficients.len() <= j {
+                            coefficients.resize(j + 1, Zero::zero());
+                        }
+                        coefficients[j] = value;
+                    }
+                }
+                Some(Section::Rhs) | Some(Section::Ranges) => {
+                    // The name of the vector is optional in the free format
+                    let entries = if fields.len() % 2 == 1 {
+                        &fields[1..]
+                    } else {
+                        &fields[..]
+                    };
+                    for (row, value) in pairs(entries, n)? {
+                        let value = parse_number(value, n)?;
+                        match (find_row(&rows, row, n)?, section) {
+                            (Row::Objective, _) => {
+                                return Err(format!(
+                                    "Рядок {n}: стала цільової функції не підтримується"
+                                ))
+                            }
+                            (Row::Free, _) => {}
+                            (Row::Constraint(i), Some(Section::Rhs)) => constraints[i].rhs = value,
+                            (Row::Constraint(i), _) => constraints[i].range = Some(value),
+                        }
+                    }
+                }
+                Some(Section::Bounds) => {
+                    let (bound_type, column, value) = match fields[..] {
+                        [bound_type, _, column, value] => (bound_type, column, Some(value)),
+                        [bound_type, _, column] => (bound_type, column, None),
+                        _ => {
+                            return Err(format!(
+                                "Рядок {n}: очікується тип, назва та стовпець межі"
+                            ))
+                        }
+                    };
+                    let Some(&j) = columns.get(column) else {
+                        return Err(format!("Рядок {n}: невідомий стовпець {column:?}"));
+                    };
+                    let value = value.map(|value| parse_number(value, n)).transpose()?;
+                    match (bound_type, value) {
+                        ("UP" | "UI", Some(value)) => bounds.push((j, Sign::Less, value)),
+                        ("LO" | "LI", Some(value)) if value < BigRationalExt::zero() => {
+                            return Err(format!(
+                                "Рядок {n}: змінні невід'ємні, від'ємна нижня межа не підтримується"
+                            ))
+                        }
+                        ("LO" | "LI", Some(value)) if value.is_zero() => {}
+                        ("LO" | "LI", Some(value)) => bounds.push((j, Sign::Greater, value)),
+                        ("FX", Some(value)) => bounds.push((j, Sign::Equals, value)),
+                        ("BV", _) => bounds.push((j, Sign::Less, BigRationalExt::one())),
+                        ("PL", _) => {}
+                        _ => {
+                            return Err(format!(
+                                "Рядок {n}: тип межі {bound_type:?} не підтримується"
+                            ))
+                        }
+                    }
+                }
+            }
+        }
+
+        if !rows.values().any(|row| row == &Row::Objective) {
+            return Err("Відсутній рядок цільової функції".to_owned());
+        }
+
+        let n_columns = columns.len();
+        let row = |mut coefficients: Vec<BigRationalExt>| {
+            coefficients.resize(n_columns, Zero::zero());
+            RowDVector::from_vec(coefficients)
+        };
+        let constraints = constraints
+            .into_iter()
+            .flat_map(
+                |MpsConstraint {
+                     coefficients,
+                     sign,
+                     rhs,
+                     range,
+                 }| {
+                    let coefficients = row(coefficients);
+                    let ranged = range.map(|range| {
+                        let (lower, upper) = range_bounds(sign, &rhs, range);
+                        [
+                            Constraint::new(coefficients.clone(), Sign::Greater, lower),
+                            Constraint::new(coefficients.clone(), Sign::Less, upper),
+                        ]
+                    });
+                    match ranged {
+                        Some(ranged) => ranged.to_vec(),
+                        None => vec![Constraint::new(coefficients, sign, rhs)],
+                    }
+                },
+            )
+            .chain(bounds.into_iter().map(|(j, sign, value)| {
+                let mut coefficients = vec![BigRationalExt::zero(); j + 1];
+                coefficients[j] = BigRationalExt::one();
+                Constraint::new(row(coefficients), sign, value)
+            }))
+            .collect();
+
+        Problem::new(
+            ObjectiveFunction::new(row(objective), minimize),
+            constraints,
+        )
+        .map_err(|err| err.to_string())
+    }
+}
+
+/// `[lower, upper]` of the row `sign rhs` with the `range` `R`, as the MPS format defines it
+fn range_bounds(
+    sign: Sign,
+    rhs: &BigRationalExt,
+    range: BigRationalExt,
+) -> (BigRationalExt, BigRationalExt) {
+    let abs = if range < BigRationalExt::zero() {
+        -range.clone()
+    } else {
+        range.clone()
+    };
+    match sign {
+        Sign::Less => (rhs - &abs, rhs.clone()),
+        Sign::Greater => (rhs.clone(), rhs + &abs),
+        Sign::Equals if range < BigRationalExt::zero() => (rhs + &range, rhs.clone()),
+        Sign::Equals => (rhs.clone(), rhs + &range),
+    }
+}
+
+fn parse_sense(sense: &str, n: usize) -> Result<bool, String> {
+    match sense {
+        "MIN" | "MINIMIZE" => Ok(true),
+        "MAX" | "MAXIMIZE" => Ok(false),
+        _ => Err(format!("Рядок {n}: невідомий напрям оптимізації {sense:?}")),
+    }
+}
+
+fn parse_number(value: &str, n: usize) -> Result<BigRationalExt, String> {
+    BigRationalExt::from_decimal_str(value)
+        .map_err(|_| format!("Рядок {n}: некоректне число {value:?}"))
+}
+
+/// Pairs of the row names and the values, that follow the name of a column or of a vector
+fn pairs<'a>(entries: &[&'a str], n: usize) -> Result<Vec<(&'a str, &'a str)>, String> {
+    if entries.is_empty() || entries.len() % 2 == 1 {
+        return Err(format!("Рядок {n}: очікуються пари назв рядків та значень"));
+    }
+    Ok(entries.chunks(2).map(|pair| (pair[0], pair[1])).collect())
+}
+
+fn find_row(rows: &HashMap<&str, Row>, name: &str, n: usize) -> Result<Row, String> {
+    rows.get(name)
+        .copied()
+        .ok_or_else(|| format!("Рядок {n}: невідомий рядок {name:?}"))
+}
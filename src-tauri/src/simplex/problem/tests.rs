@@ -1,9 +1,18 @@
+use num_rational::BigRational;
 use pretty_assertions::assert_str_eq;
+use ratio_extension::{ratio_vec_from_floats, RatioExt};
+
+use crate::simplex::{SimplexSolver, SimplexTable, SolveWarning, StepOutcome};
 
 use super::*;
 
 fn prepare_problem() -> Problem {
-    Problem::normalize(
+    let (objective_function, constraints) = prepare_problem_input();
+    Problem::normalize(objective_function, constraints)
+}
+
+fn prepare_problem_input() -> (ObjectiveFunction<BigRationalExt>, Vec<Constraint>) {
+    (
         ObjectiveFunction::new(
             RowDVector::from_iterator(
                 12,
@@ -88,6 +97,62 @@ fn prepare_problem() -> Problem {
     )
 }
 
+/// Beale's example: with the first-wins tie-break the same six bases repeat forever
+fn prepare_beale_problem() -> Problem {
+    Problem::new(
+        ObjectiveFunction::new(
+            RowDVector::from_iterator(
+                4,
+                [-0.75, 20., -0.5, 6.]
+                    .into_iter()
+                    .map(BigRationalExt::from_float),
+            ),
+            true,
+        ),
+        vec![
+            Constraint::new(
+                RowDVector::from_iterator(
+                    4,
+                    [0.25, -8., -1., 9.]
+                        .into_iter()
+                        .map(BigRationalExt::from_float),
+                ),
+                Sign::Less,
+                BigRationalExt::zero(),
+            ),
+            Constraint::new(
+                RowDVector::from_iterator(
+                    4,
+                    [0.5, -12., -0.5, 3.]
+                        .into_iter()
+                        .map(BigRationalExt::from_float),
+                ),
+                Sign::Less,
+                BigRationalExt::zero(),
+            ),
+            Constraint::new(
+                RowDVector::from_iterator(
+                    4,
+                    [0., 0., 1., 0.].into_iter().map(BigRationalExt::from_float),
+                ),
+                Sign::Less,
+                BigRationalExt::one(),
+            ),
+        ],
+    )
+    .unwrap()
+}
+
+/// Steps the [`SimplexTable`] to the end, so that it can be inspected at the optimum
+fn solve_table(problem: Problem) -> (SimplexTable, SolutionResult) {
+    let mut table = SimplexTable::new(problem).unwrap();
+    let (mut solution, mut prev_pivot_col) = (None, None);
+    while solution.is_none() {
+        (solution, prev_pivot_col) = table.step(prev_pivot_col, &|_| {});
+    }
+    (table, solution.unwrap())
+}
+
 #[test]
 fn problem_add_constraint_on_var_works_for_3_less_0() {
     let mut problem = prepare_problem();
@@ -294,6 +359,43 @@ fn problem_add_constraint_on_var_works_for_3_greater_2() {
     );
 }
 
+#[test]
+fn problem_remove_last_constraint_undoes_add_constraint_on_var() {
+    let problem = prepare_problem();
+
+    for (sign, rhs) in [(Sign::Less, 0.), (Sign::Less, 1.), (Sign::Greater, 2.)] {
+        let mut changed = problem.clone();
+        changed.add_constraint_on_var(3, sign, BigRationalExt::from_float(rhs));
+        changed.remove_last_constraint();
+        assert_eq!(changed, problem);
+    }
+
+    // The artificial variable of the `<=` row is redundant, so the last column belongs to another row
+    let mut changed = problem.clone();
+    changed.add_constraint_on_var(3, Sign::Less, BigRationalExt::one());
+    changed.remove_redundant_artificials();
+    assert_eq!(changed.n_artificial(), problem.n_artificial());
+    changed.remove_last_constraint();
+    assert_eq!(changed, problem);
+}
+
+#[test]
+fn problem_remove_variable() {
+    let mut problem = prepare_problem();
+    problem.remove_variable(0);
+    assert_eq!(problem.n_original(), 11);
+    assert_eq!(problem.constraints.ncols(), 22);
+    assert_eq!(problem.objective_function.coefficients.ncols(), 22);
+    assert_eq!(problem.constraints[(0, 0)], BigRationalExt::from_float(30.));
+
+    problem.remove_variable(11);
+    assert_eq!(problem.n_slack(), 3);
+    assert_eq!(
+        problem.n_original() + problem.n_slack() + problem.n_artificial(),
+        problem.constraints.ncols()
+    );
+}
+
 #[test]
 fn problem_normalize_works_with_my_variant() {
     let problem = prepare_problem();
@@ -357,3 +459,1742 @@ fn problem_normalize_works_with_my_variant() {
         DVector::from_column_slice(&[300., 200., 1000., 500., 50., 20., 30.]).to_string()
     )
 }
+
+#[test]
+fn problem_normalize_is_fast_with_many_inequalities() {
+    // x_k <= 1 and x_k >= 0 for every k
+    let n = 300;
+    let constraints = (0..n)
+        .flat_map(|k| {
+            let mut coefficients = RowDVector::zeros(n);
+            coefficients[k] = BigRationalExt::one();
+            [
+                Constraint::new(coefficients.clone(), Sign::Less, BigRationalExt::one()),
+                Constraint::new(coefficients, Sign::Greater, BigRationalExt::zero()),
+            ]
+        })
+        .collect();
+
+    let start = std::time::Instant::now();
+    let problem = Problem::normalize(
+        ObjectiveFunction::new(RowDVector::from_element(n, BigRationalExt::one()), true),
+        constraints,
+    );
+    let elapsed = start.elapsed();
+
+    assert!(
+        elapsed < std::time::Duration::from_secs(5),
+        "Normalized in {elapsed:?}"
+    );
+    assert_eq!(problem.n_slack(), 2 * n);
+    assert_eq!(problem.constraints.ncols(), n + 2 * n + 2 * n);
+    for i in 0..2 * n {
+        let expected = if i % 2 == 0 {
+            BigRationalExt::one()
+        } else {
+            -BigRationalExt::one()
+        };
+        assert_eq!(problem.constraints[(i, n + i)], expected);
+    }
+}
+
+#[test]
+fn constraint_display() {
+    let constraint = Constraint::new(
+        RowDVector::from_iterator(
+            4,
+            [3., 0.5, 0., -1.]
+                .into_iter()
+                .map(BigRationalExt::from_float),
+        ),
+        Sign::Less,
+        BigRationalExt::from_float(5.),
+    );
+    assert_str_eq!(constraint.to_string(), "3x1 + 1/2x2 - x4 <= 5");
+
+    let constraint = Constraint::new(
+        RowDVector::from_iterator(2, [-2., 1.].into_iter().map(BigRationalExt::from_float)),
+        Sign::Equals,
+        BigRationalExt::from_float(-1.5),
+    );
+    assert_str_eq!(constraint.to_string(), "-2x1 + x2 = -3/2");
+}
+
+#[test]
+fn constraint_with_nonnegative_rhs_flips_the_inequalities() {
+    let constraint = |coefficients: &[f64], sign, rhs| {
+        Constraint::new(
+            ratio_vec_from_floats(coefficients),
+            sign,
+            BigRationalExt::from_float(rhs),
+        )
+    };
+    for (sign, flipped) in [
+        (Sign::Less, Sign::Greater),
+        (Sign::Equals, Sign::Equals),
+        (Sign::Greater, Sign::Less),
+    ] {
+        let normalized = constraint(&[2., -1.], sign, -3.).with_nonnegative_rhs();
+        assert_eq!(normalized, constraint(&[-2., 1.], flipped, 3.));
+        assert!(normalized.rhs() >= &BigRationalExt::zero());
+        // Idempotent
+        assert_eq!(normalized.clone().with_nonnegative_rhs(), normalized);
+
+        let nonnegative = constraint(&[2., -1.], sign, 3.);
+        assert_eq!(nonnegative.clone().with_nonnegative_rhs(), nonnegative);
+    }
+}
+
+#[test]
+fn problem_new_rejects_empty_problems() {
+    assert_eq!(
+        Problem::new(
+            ObjectiveFunction::new(RowDVector::zeros(0), true),
+            vec![Constraint::new(
+                RowDVector::zeros(0),
+                Sign::Less,
+                BigRationalExt::one()
+            )],
+        ),
+        Err(ProblemError::NoVariables)
+    );
+    assert_eq!(
+        Problem::new(
+            ObjectiveFunction::new(
+                RowDVector::from_iterator(2, [1., 1.].into_iter().map(BigRationalExt::from_float)),
+                true,
+            ),
+            vec![],
+        ),
+        Err(ProblemError::NoConstraints)
+    );
+}
+
+#[test]
+fn problem_normalize_flips_rows_with_negative_rhs() {
+    let normalize = |sign| {
+        Problem::normalize(
+            ObjectiveFunction::new(
+                RowDVector::from_iterator(2, [1., 1.].into_iter().map(BigRationalExt::from_float)),
+                true,
+            ),
+            vec![Constraint::new(
+                RowDVector::from_iterator(2, [1., -2.].into_iter().map(BigRationalExt::from_float)),
+                sign,
+                BigRationalExt::from_float(-3.),
+            )],
+        )
+    };
+
+    // -x1 + 2x2 >= 3
+    let problem = normalize(Sign::Less);
+    assert_str_eq!(
+        problem.constraints.to_string(),
+        DMatrix::from_row_slice(1, 4, &[-1., 2., -1., 1.]).to_string()
+    );
+    assert_str_eq!(
+        problem.rhs.to_string(),
+        DVector::from_column_slice(&[3.]).to_string()
+    );
+
+    // -x1 + 2x2 <= 3
+    let problem = normalize(Sign::Greater);
+    assert_str_eq!(
+        problem.constraints.to_string(),
+        DMatrix::from_row_slice(1, 4, &[-1., 2., 1., 1.]).to_string()
+    );
+    assert_str_eq!(
+        problem.rhs.to_string(),
+        DVector::from_column_slice(&[3.]).to_string()
+    );
+
+    // -x1 + 2x2 = 3
+    let problem = normalize(Sign::Equals);
+    assert_str_eq!(
+        problem.constraints.to_string(),
+        DMatrix::from_row_slice(1, 3, &[-1., 2., 1.]).to_string()
+    );
+    assert_str_eq!(
+        problem.rhs.to_string(),
+        DVector::from_column_slice(&[3.]).to_string()
+    );
+}
+
+#[test]
+fn problem_new_pads_ragged_rows_with_zeros() {
+    let row = |coefficients: &[f64]| {
+        RowDVector::from_iterator(
+            coefficients.len(),
+            coefficients.iter().copied().map(BigRationalExt::from_float),
+        )
+    };
+    let problem = Problem::new(
+        ObjectiveFunction::new(row(&[1., 2.]), true),
+        vec![
+            Constraint::new(row(&[1.]), Sign::Less, BigRationalExt::from_float(4.)),
+            Constraint::new(row(&[0., 0., 3.]), Sign::Greater, BigRationalExt::one()),
+            Constraint::new(row(&[1., 1.]), Sign::Equals, BigRationalExt::from_float(2.)),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(problem.n_original(), 3);
+    assert_str_eq!(
+        problem.constraints.to_string(),
+        DMatrix::from_row_slice(
+            3,
+            8,
+            &[
+                1., 0., 0., 1., 0., 1., 0., 0., //
+                0., 0., 3., 0., -1., 0., 1., 0., //
+                1., 1., 0., 0., 0., 0., 0., 1., //
+            ]
+        )
+        .to_string()
+    );
+    assert_str_eq!(
+        problem
+            .objective_function
+            .coefficients
+            .columns(0, 5)
+            .to_string(),
+        RowDVector::from_row_slice(&[1., 2., 0., 0., 0.]).to_string()
+    );
+
+    let solution = problem.solve().unwrap();
+    assert_eq!(solution.fn_val, BigRational::from_integer(2.into()));
+    assert_eq!(
+        solution.vars,
+        [
+            BigRational::from_integer(2.into()),
+            BigRational::zero(),
+            BigRational::new(1.into(), 3.into()),
+        ]
+    );
+}
+
+#[test]
+fn problem_variable_roles_work_with_my_variant() {
+    let mut problem = prepare_problem();
+    assert_eq!(problem.n_original(), 12);
+    assert_eq!(problem.n_slack(), 4);
+    assert_eq!(problem.n_artificial(), 7);
+    assert_eq!(
+        problem.n_original() + problem.n_slack() + problem.n_artificial(),
+        problem.constraints.ncols()
+    );
+    assert_eq!(problem.variable_role(11), Some(VariableRole::Original));
+    assert_eq!(problem.variable_role(12), Some(VariableRole::Slack));
+    assert_eq!(problem.variable_role(16), Some(VariableRole::Artificial));
+    assert_eq!(problem.variable_role(23), None);
+
+    problem.add_constraint_on_var(3, Sign::Less, BigRationalExt::one());
+    assert_eq!(problem.n_slack(), 5);
+    assert_eq!(problem.n_artificial(), 8);
+    assert_eq!(
+        problem.n_original() + problem.n_slack() + problem.n_artificial(),
+        problem.constraints.ncols()
+    );
+}
+
+#[test]
+fn problem_solve_observed_reports_iterations_and_pivots() {
+    // min x1 + x2; x1 + 2x2 >= 3; x1 <= 4
+    let problem = Problem::new(
+        ObjectiveFunction::new(
+            RowDVector::from_iterator(2, [1., 1.].into_iter().map(BigRationalExt::from_float)),
+            true,
+        ),
+        vec![
+            Constraint::new(
+                RowDVector::from_iterator(2, [1., 2.].into_iter().map(BigRationalExt::from_float)),
+                Sign::Greater,
+                BigRationalExt::from_float(3.),
+            ),
+            Constraint::new(
+                RowDVector::from_iterator(2, [1., 0.].into_iter().map(BigRationalExt::from_float)),
+                Sign::Less,
+                BigRationalExt::from_float(4.),
+            ),
+        ],
+    )
+    .unwrap();
+
+    let events = Mutex::new(Vec::new());
+    let solution = problem
+        .solve_observed(&|event| events.lock().unwrap().push(event))
+        .unwrap();
+
+    assert_eq!(solution.fn_val, BigRational::new(3.into(), 2.into()));
+    assert_eq!(
+        events.into_inner().unwrap(),
+        vec![
+            SolveEvent::IterationStarted {
+                n: 1,
+                estimation: BigNumber::new(BigRationalExt::from_float(7.), Zero::zero()),
+            },
+            // x1 and x2 are tied, the one with the smaller index enters
+            SolveEvent::PivotChosen { row: 0, col: 0 },
+            SolveEvent::IterationStarted {
+                n: 2,
+                estimation: BigNumber::new(
+                    BigRationalExt::from_float(1.),
+                    BigRationalExt::from_float(3.)
+                ),
+            },
+            SolveEvent::PivotChosen { row: 1, col: 3 },
+            SolveEvent::IterationStarted {
+                n: 3,
+                estimation: BigNumber::new(Zero::zero(), BigRationalExt::from_float(3.)),
+            },
+            SolveEvent::PivotChosen { row: 0, col: 1 },
+            SolveEvent::IterationStarted {
+                n: 4,
+                estimation: BigNumber::new(Zero::zero(), BigRationalExt::from_float(1.5)),
+            },
+        ]
+    );
+}
+
+#[test]
+fn simplex_solver_steps_to_the_same_solution_as_solve() {
+    let problem = prepare_problem();
+    let expected = problem.clone().solve().unwrap();
+
+    let mut solver = SimplexSolver::new(problem).unwrap();
+    let initial_tableau = solver.current_tableau().clone();
+    let mut n_pivots = 0;
+    let solution = loop {
+        match solver.next_step() {
+            StepOutcome::Pivoted { row, col } => {
+                assert_eq!(solver.current_tableau()[(row, col)], BigRationalExt::one());
+                n_pivots += 1;
+            }
+            StepOutcome::Optimal(solution) => break solution,
+            outcome => panic!("Unexpected outcome: {outcome:?}"),
+        }
+    };
+
+    assert!(n_pivots > 0);
+    assert_ne!(solver.current_tableau(), &initial_tableau);
+    assert_eq!(solution, expected);
+    assert_eq!(solver.next_step(), StepOutcome::Optimal(expected));
+}
+
+#[test]
+fn problem_feasible_point_satisfies_the_constraints() {
+    let row = |coefficients: &[f64]| {
+        RowDVector::from_iterator(
+            coefficients.len(),
+            coefficients.iter().copied().map(BigRationalExt::from_float),
+        )
+    };
+    let constraints = vec![
+        Constraint::new(
+            row(&[1., 1., 0.]),
+            Sign::Greater,
+            BigRationalExt::from_float(2.),
+        ),
+        Constraint::new(row(&[1., -1., 0.]), Sign::Less, BigRationalExt::one()),
+        Constraint::new(
+            row(&[1., 2., 1.]),
+            Sign::Equals,
+            BigRationalExt::from_float(5.),
+        ),
+        Constraint::new(
+            row(&[0., 1., -3.]),
+            Sign::Less,
+            BigRationalExt::from_float(-1.),
+        ),
+    ];
+    let problem = Problem::new(
+        ObjectiveFunction::new(row(&[-1., -1., -1.]), true),
+        constraints.clone(),
+    )
+    .unwrap();
+
+    let point = problem.feasible_point().unwrap();
+    assert_eq!(point.len(), 3);
+    assert!(point.iter().all(|var| var >= &BigRationalExt::zero()));
+    for constraint in &constraints {
+        let lhs = constraint
+            .coefficients()
+            .iter()
+            .zip(&point)
+            .map(|(coefficient, var)| coefficient * var)
+            .sum::<BigRationalExt>();
+        let satisfied = match constraint.sign() {
+            Sign::Less => &lhs <= constraint.rhs(),
+            Sign::Equals => &lhs == constraint.rhs(),
+            Sign::Greater => &lhs >= constraint.rhs(),
+        };
+        assert!(satisfied, "{constraint} is violated by {point:?}");
+    }
+
+    let infeasible = Problem::new(
+        ObjectiveFunction::new(row(&[1.]), true),
+        vec![
+            Constraint::new(row(&[1.]), Sign::Less, BigRationalExt::one()),
+            Constraint::new(row(&[1.]), Sign::Greater, BigRationalExt::from_float(2.)),
+        ],
+    )
+    .unwrap();
+    assert_eq!(infeasible.feasible_point(), None);
+}
+
+#[test]
+fn problem_verify_reports_violated_rows() {
+    let problem = prepare_problem();
+    let solution = problem.clone().solve().unwrap();
+    assert_eq!(problem.verify(&solution), Ok(()));
+
+    let mut tampered = solution.clone();
+    tampered.vars[0] -= BigRational::one();
+    let int = |n: i64| BigRationalExt::from_integer(n.into());
+    assert_eq!(
+        problem.verify(&tampered),
+        Err(vec![
+            ConstraintViolation::Constraint {
+                row: 4,
+                lhs: int(49),
+                sign: Sign::Equals,
+                rhs: int(50),
+            },
+            ConstraintViolation::Objective {
+                expected: int(2549),
+                actual: int(2564),
+            },
+        ])
+    );
+
+    let mut short = solution;
+    short.vars.pop();
+    assert_eq!(
+        problem.verify(&short),
+        Err(vec![ConstraintViolation::VariableCount {
+            expected: 12,
+            found: 11,
+        }])
+    );
+}
+
+#[test]
+fn simplex_solver_enters_the_lowest_of_tied_columns() {
+    let problem = Problem::new(
+        ObjectiveFunction::new(RowDVector::from_element(2, -BigRationalExt::one()), true),
+        vec![Constraint::new(
+            RowDVector::from_element(2, BigRationalExt::one()),
+            Sign::Less,
+            BigRationalExt::from_float(4.),
+        )],
+    )
+    .unwrap();
+
+    let mut solver = SimplexSolver::new(problem).unwrap();
+    assert_eq!(solver.next_step(), StepOutcome::Pivoted { row: 0, col: 0 });
+}
+
+#[test]
+fn problem_solve_with_lexicographic_ratio_test_does_not_cycle() {
+    let problem = prepare_beale_problem().with_lexicographic_ratio_test(true);
+
+    let solution = problem.solve().unwrap();
+    assert_eq!(solution.fn_val, BigRational::new((-5).into(), 4.into()));
+    assert_eq!(
+        solution.vars,
+        [1, 0, 1, 0].map(|var| BigRational::from_integer(var.into()))
+    );
+}
+
+#[test]
+fn problem_solve_reports_objective_in_the_sense_of_maximization() {
+    let problem = Problem::new(
+        ObjectiveFunction::new(
+            RowDVector::from_element(1, BigRationalExt::from_float(2.)),
+            false,
+        ),
+        vec![Constraint::new(
+            RowDVector::from_element(1, BigRationalExt::one()),
+            Sign::Less,
+            BigRationalExt::from_float(3.),
+        )],
+    )
+    .unwrap();
+
+    let solution = problem.solve().unwrap();
+    assert_eq!(solution.vars, [BigRational::from_integer(3.into())]);
+    assert_eq!(solution.fn_val, BigRational::from_integer((-6).into()));
+    assert_eq!(
+        solution.objective_in(false),
+        BigRational::from_integer(6.into())
+    );
+}
+
+#[test]
+fn problem_solve_with_stats_warns_about_degenerate_ties() {
+    let (solution, stats) = prepare_beale_problem()
+        .with_lexicographic_ratio_test(true)
+        .solve_with_stats();
+
+    assert_eq!(
+        solution.unwrap().fn_val,
+        BigRational::new((-5).into(), 4.into())
+    );
+    assert!(stats
+        .warnings
+        .contains(&SolveWarning::ManyDegenerateTies { count: 2 }));
+}
+
+#[test]
+fn problem_solve_with_whole_is_deterministic_on_ties() {
+    // min x1 + x2; x1 + 2x2 >= 3; x1 <= 4
+    // Both (1, 1) and (0, 2) are integer optima
+    let problem = Problem::new(
+        ObjectiveFunction::new(
+            RowDVector::from_iterator(2, [1., 1.].into_iter().map(BigRationalExt::from_float)),
+            true,
+        ),
+        vec![
+            Constraint::new(
+                RowDVector::from_iterator(2, [1., 2.].into_iter().map(BigRationalExt::from_float)),
+                Sign::Greater,
+                BigRationalExt::from_float(3.),
+            ),
+            Constraint::new(
+                RowDVector::from_iterator(2, [1., 0.].into_iter().map(BigRationalExt::from_float)),
+                Sign::Less,
+                BigRationalExt::from_float(4.),
+            ),
+        ],
+    )
+    .unwrap();
+
+    for _ in 0..32 {
+        let solution = problem.clone().solve_with_whole().unwrap();
+        assert_eq!(solution.fn_val, BigRational::from_integer(2.into()));
+        assert_eq!(
+            solution.vars,
+            vec![
+                BigRational::from_integer(0.into()),
+                BigRational::from_integer(2.into())
+            ]
+        );
+    }
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn problem_solve_with_whole_stays_within_thread_pool() {
+    use std::sync::atomic::{self, AtomicBool};
+
+    // min x1 + x2 + x3 + x4; 2x1 + 2x2 + 2x3 + 2x4 >= 7
+    // The relaxation keeps staying fractional, so the tree is about a hundred nodes deep and wide
+    let problem = Problem::new(
+        ObjectiveFunction::new(
+            RowDVector::from_iterator(4, [1.; 4].into_iter().map(BigRationalExt::from_float)),
+            true,
+        ),
+        vec![Constraint::new(
+            RowDVector::from_iterator(4, [2.; 4].into_iter().map(BigRationalExt::from_float)),
+            Sign::Greater,
+            BigRationalExt::from_float(7.),
+        )],
+    )
+    .unwrap();
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(2)
+        .build()
+        .unwrap();
+    let outside_of_pool = AtomicBool::new(false);
+    let solution = pool
+        .install(|| {
+            problem.solve_with_whole_observed(&|_| {
+                if !matches!(rayon::current_thread_index(), Some(0 | 1)) {
+                    outside_of_pool.store(true, atomic::Ordering::Relaxed);
+                }
+            })
+        })
+        .unwrap();
+
+    assert!(!outside_of_pool.into_inner());
+    assert_eq!(solution.fn_val, BigRational::from_integer(4.into()));
+}
+
+/// Runs in both `parallel` and `--no-default-features` builds, the optimum must not depend on it
+#[test]
+fn problem_solve_with_whole_works_with_my_variant() {
+    let solution = prepare_problem().solve_with_whole().unwrap();
+
+    assert_eq!(solution.fn_val, BigRational::from_integer(2564.into()));
+    assert_eq!(
+        solution.vars,
+        [40, 0, 0, 0, 8, 0, 0, 12, 30, 10, 0, 0]
+            .map(|var| BigRational::from_integer(var.into()))
+            .to_vec()
+    );
+}
+
+#[test]
+fn problem_solve_with_whole_iter_yields_improving_incumbents() {
+    let incumbents = prepare_problem()
+        .solve_with_whole_iter()
+        .collect::<Vec<_>>();
+
+    assert!(!incumbents.is_empty());
+    assert!(incumbents
+        .windows(2)
+        .all(|pair| pair[1].is_better_than(&pair[0])));
+    assert_eq!(
+        incumbents.last(),
+        Some(&prepare_problem().solve_with_whole().unwrap())
+    );
+}
+
+#[test]
+fn problem_solve_ref_agrees_with_solve() {
+    // max 3x1 + 5x2; x1 <= 4; 3x1 + 2x2 <= 18
+    let maximization = Problem::new(
+        ObjectiveFunction::new(ratio_vec_from_floats(&[3., 5.]), false),
+        vec![
+            Constraint::new(
+                ratio_vec_from_floats(&[1., 0.]),
+                Sign::Less,
+                BigRationalExt::from_float(4.),
+            ),
+            Constraint::new(
+                ratio_vec_from_floats(&[3., 2.]),
+                Sign::Less,
+                BigRationalExt::from_float(18.),
+            ),
+        ],
+    )
+    .unwrap();
+    for problem in [
+        prepare_problem(),
+        prepare_beale_problem().with_lexicographic_ratio_test(true),
+        maximization,
+    ] {
+        // The table from the reference takes the big coefficients as they are,
+        // while `SimplexTable::new` used to convert them into the big numbers a second time
+        assert_eq!(
+            SimplexTable::from_ref(&problem),
+            SimplexTable::new(problem.clone())
+        );
+        assert_eq!(problem.solve_ref(), problem.clone().solve());
+    }
+}
+
+#[test]
+fn simplex_table_takes_the_objective_coefficients_as_they_are() {
+    let problem = prepare_problem();
+    let table = SimplexTable::new(problem.clone()).unwrap();
+    assert_eq!(
+        table.coefficients(),
+        &problem.objective_function.coefficients
+    );
+
+    // max 3x1 + 5x2; x1 <= 4
+    let maximization = Problem::new(
+        ObjectiveFunction::new(ratio_vec_from_floats(&[3., 5.]), false),
+        vec![Constraint::new(
+            ratio_vec_from_floats(&[1., 0.]),
+            Sign::Less,
+            BigRationalExt::from_float(4.),
+        )],
+    )
+    .unwrap();
+    let table = SimplexTable::new(maximization.clone()).unwrap();
+    assert_eq!(
+        table.coefficients(),
+        &-maximization.objective_function.coefficients
+    );
+}
+
+#[test]
+fn simplex_table_new_rejects_a_malformed_basis() {
+    let mut problem = prepare_problem();
+    problem.objective_function.coefficients[22] = BigNumber::zero();
+    assert_eq!(
+        SimplexTable::new(problem.clone()).unwrap_err(),
+        ProblemError::BasisSize {
+            expected: 7,
+            found: 6
+        }
+    );
+    assert_eq!(
+        problem.solve(),
+        Err(SolutionError::Problem(ProblemError::BasisSize {
+            expected: 7,
+            found: 6
+        }))
+    );
+
+    let mut problem = prepare_problem();
+    problem.constraints[(1, 16)] = BigRationalExt::one();
+    assert_eq!(
+        SimplexSolver::new(problem).unwrap_err(),
+        ProblemError::NonIdentityBasis { column: 16 }
+    );
+}
+
+#[test]
+fn solution_iterates_and_indexes_the_variables() {
+    let solution = prepare_problem().solve().unwrap();
+
+    assert_eq!(solution.iter_vars().count(), 12);
+    assert!(solution.iter_vars().eq(&solution.vars));
+    assert!((&solution).into_iter().eq(&solution.vars));
+    assert_eq!(solution[0], BigRational::from_integer(40.into()));
+    assert_eq!(solution[2], BigRational::new(2.into(), 3.into()));
+    assert_eq!(
+        solution.clone().into_iter().collect::<Vec<_>>(),
+        solution.vars
+    );
+}
+
+#[test]
+fn problem_scale_to_integer_coefficients_keeps_the_optimum() {
+    let ratio = |n: i32, d: i32| BigRationalExt::Finite(BigRational::new(n.into(), d.into()));
+    // min 1/2 x1 + 2/5 x2; 1/4 x1 + 1/6 x2 >= 1; 2/3 x1 <= 5/3
+    let problem = Problem::new(
+        ObjectiveFunction::new(
+            RowDVector::from_row_slice(&[ratio(1, 2), ratio(2, 5)]),
+            true,
+        ),
+        vec![
+            Constraint::new(
+                RowDVector::from_row_slice(&[ratio(1, 4), ratio(1, 6)]),
+                Sign::Greater,
+                BigRationalExt::one(),
+            ),
+            Constraint::new(
+                RowDVector::from_row_slice(&[ratio(2, 3), BigRationalExt::zero()]),
+                Sign::Less,
+                ratio(5, 3),
+            ),
+        ],
+    )
+    .unwrap();
+    let mut scaled = problem.clone();
+    scaled.scale_to_integer_coefficients();
+
+    assert!(scaled
+        .constraints
+        .iter()
+        .chain(&scaled.rhs)
+        .all(|el| matches!(el, RatioExt::Finite(ratio) if ratio.is_integer())));
+    assert!(scaled
+        .objective_function
+        .coefficients
+        .iter()
+        .all(|coefficient| matches!(coefficient.small_part(), RatioExt::Finite(ratio) if ratio.is_integer())));
+
+    let expected = problem.solve().unwrap();
+    assert_eq!(
+        expected.vars,
+        vec![
+            BigRational::new(5.into(), 2.into()),
+            BigRational::new(9.into(), 4.into())
+        ]
+    );
+    let solution = scaled.clone().solve().unwrap();
+    assert_eq!(solution, expected);
+    assert_eq!(scaled.verify(&solution), Ok(()));
+}
+
+#[test]
+fn simplex_table_rhs_ranging_matches_the_textbook() {
+    // Wyndor Glass: max 3x1 + 5x2; x1 <= 4; 2x2 <= 12; 3x1 + 2x2 <= 18
+    let problem = Problem::new(
+        ObjectiveFunction::new(ratio_vec_from_floats(&[3., 5.]), false),
+        [([1., 0.], 4.), ([0., 2.], 12.), ([3., 2.], 18.)]
+            .into_iter()
+            .map(|(coefficients, rhs)| {
+                Constraint::new(
+                    RowDVector::from_iterator(
+                        2,
+                        coefficients.into_iter().map(BigRationalExt::from_float),
+                    ),
+                    Sign::Less,
+                    BigRationalExt::from_float(rhs),
+                )
+            })
+            .collect(),
+    )
+    .unwrap();
+
+    let mut reduced = problem.clone();
+    reduced.remove_redundant_artificials();
+    let (table, solution) = solve_table(problem);
+    assert_eq!(
+        solution.unwrap().vars,
+        vec![
+            BigRational::from_integer(2.into()),
+            BigRational::from_integer(6.into())
+        ]
+    );
+
+    let int = |n: i32| BigRationalExt::from_integer(n.into());
+    assert_eq!(
+        table.rhs_ranging(),
+        vec![
+            (int(2), BigRationalExt::Inf),
+            (int(6), int(18)),
+            (int(12), int(24))
+        ]
+    );
+    // The slack variables are the initial basis instead of the artificial ones
+    assert_eq!(reduced.n_artificial(), 0);
+    assert_eq!(solve_table(reduced).0.rhs_ranging(), table.rhs_ranging());
+}
+
+#[test]
+fn problem_dual_has_the_same_optimal_objective() {
+    // Wyndor Glass: max 3x1 + 5x2; x1 <= 4; 2x2 <= 12; 3x1 + 2x2 <= 18
+    let primal = Problem::new(
+        ObjectiveFunction::new(ratio_vec_from_floats(&[3., 5.]), false),
+        [([1., 0.], 4.), ([0., 2.], 12.), ([3., 2.], 18.)]
+            .into_iter()
+            .map(|(coefficients, rhs)| {
+                Constraint::new(
+                    RowDVector::from_iterator(
+                        2,
+                        coefficients.into_iter().map(BigRationalExt::from_float),
+                    ),
+                    Sign::Less,
+                    BigRationalExt::from_float(rhs),
+                )
+            })
+            .collect(),
+    )
+    .unwrap();
+    let dual = primal.dual();
+    assert!(dual.objective_function.minimization);
+    assert_eq!(dual.n_original(), 3);
+    assert_eq!(dual.constraints.nrows(), 2);
+
+    let objective = |problem: Problem| {
+        let minimization = problem.objective_function.minimization;
+        problem.solve().unwrap().objective_in(minimization)
+    };
+    assert_eq!(objective(dual), BigRational::from_integer(36.into()));
+    assert_eq!(objective(primal), BigRational::from_integer(36.into()));
+
+    // The equalities of the ship problem get two dual variables each
+    let primal = prepare_problem();
+    let dual = primal.dual();
+    assert!(!dual.objective_function.minimization);
+    assert_eq!(dual.n_original(), 4 + 2 * 3);
+    assert_eq!(objective(dual), objective(primal));
+}
+
+#[test]
+fn simplex_table_reports_the_binding_constraints() {
+    let (table, solution) = solve_table(prepare_problem());
+    assert!(solution.is_ok());
+
+    // At [40, 0, 2/3, 0, 8, 0, 0, 12, 88/3, 10, 0, 0] the first line carries
+    // 15 * 40 + 25 * 2/3 > 300, while the others carry exactly their minimum
+    assert_eq!(
+        table.binding_constraints(),
+        vec![false, true, true, true, true, true, true]
+    );
+}
+
+#[test]
+fn simplex_table_full_solution_keeps_every_column() {
+    let problem = prepare_problem();
+    let (n_columns, n_artificial) = (problem.num_variables(), problem.n_artificial());
+    let (table, solution) = solve_table(problem);
+    let solution = solution.unwrap();
+
+    let full = table.full_solution();
+    assert_eq!(full.len(), n_columns);
+    assert_eq!(
+        full[..solution.vars.len()],
+        solution
+            .vars
+            .iter()
+            .cloned()
+            .map(BigRationalExt::from)
+            .collect::<Vec<_>>()
+    );
+    // The optimum is feasible, so the artificial variables have left the basis
+    assert!(full[n_columns - n_artificial..]
+        .iter()
+        .all(BigRationalExt::is_zero));
+}
+
+#[test]
+fn problem_built_incrementally_solves_to_the_same_optimum() {
+    let expected = prepare_problem();
+    let n_original = expected.n_original();
+
+    let mut problem = Problem::empty(n_original, true);
+    assert_eq!(problem.constraints.nrows(), 0);
+    problem.set_objective(
+        expected
+            .objective_function
+            .coefficients
+            .columns(0, n_original)
+            .map(|coefficient| BigRationalExt::try_from(coefficient).unwrap()),
+    );
+    for row in 0..expected.constraints.nrows() {
+        problem.add_constraint(Constraint::new(
+            expected
+                .constraints
+                .row(row)
+                .columns(0, n_original)
+                .into_owned(),
+            expected.constraint_sign(row),
+            expected.rhs[row].clone(),
+        ));
+    }
+    assert_eq!(problem.n_slack(), expected.n_slack());
+    assert_eq!(problem.n_artificial(), expected.n_artificial());
+
+    let solution = problem.clone().solve().unwrap();
+    assert_eq!(solution.fn_val, expected.clone().solve().unwrap().fn_val);
+    assert_eq!(problem.verify(&solution), Ok(()));
+    assert_eq!(
+        problem.solve_with_whole().unwrap().fn_val,
+        expected.solve_with_whole().unwrap().fn_val
+    );
+}
+
+#[test]
+fn problem_from_rows_equals_problem_new() {
+    let from_floats = |floats: &[f64]| {
+        floats
+            .iter()
+            .copied()
+            .map(BigRationalExt::from_float)
+            .collect::<Vec<_>>()
+    };
+    let problem = Problem::from_rows(
+        from_floats(&[1., 1.]),
+        true,
+        vec![
+            (
+                from_floats(&[1., 2.]),
+                Sign::Greater,
+                BigRationalExt::from_float(3.),
+            ),
+            // Shorter rows are padded in the same way
+            (
+                from_floats(&[1.]),
+                Sign::Less,
+                BigRationalExt::from_float(4.),
+            ),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(
+        problem,
+        Problem::new(
+            ObjectiveFunction::new(RowDVector::from_vec(from_floats(&[1., 1.])), true),
+            vec![
+                Constraint::new(
+                    RowDVector::from_vec(from_floats(&[1., 2.])),
+                    Sign::Greater,
+                    BigRationalExt::from_float(3.),
+                ),
+                Constraint::new(
+                    RowDVector::from_vec(from_floats(&[1., 0.])),
+                    Sign::Less,
+                    BigRationalExt::from_float(4.),
+                ),
+            ],
+        )
+        .unwrap()
+    );
+    assert_eq!(
+        Problem::from_rows(Vec::new(), true, Vec::new()),
+        Err(ProblemError::NoVariables)
+    );
+}
+
+/// Keeps the log records at `info` and above
+struct CapturingLogger(Mutex<Vec<String>>);
+
+impl log::Log for CapturingLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Info
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn problem_solve_logs_no_iterations_at_info_level() {
+    static LOGGER: CapturingLogger = CapturingLogger(Mutex::new(Vec::new()));
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Info);
+
+    prepare_problem().solve_with_whole().unwrap();
+
+    let logged = LOGGER.0.lock().unwrap();
+    assert!(logged
+        .iter()
+        .any(|message| message.contains("Branch all integers")));
+    assert!(!logged.iter().any(|message| {
+        ["Iteration", "Function estimation", "Pivot"]
+            .iter()
+            .any(|prefix| message.starts_with(prefix))
+    }));
+}
+
+#[test]
+fn solution_is_better_than_in_both_senses() {
+    let int = |n: i32| BigRational::from_integer(n.into());
+    let solution = |fn_val, vars: &[i32]| Solution {
+        fn_val: int(fn_val),
+        vars: vars.iter().copied().map(int).collect(),
+    };
+
+    // Minimization: the smaller objective is better
+    assert!(solution(3, &[1]).is_better_than(&solution(5, &[0])));
+    assert!(!solution(5, &[0]).is_better_than(&solution(3, &[1])));
+
+    // Maximization: the objectives 5 and 3 are kept negated
+    let (five, three) = (solution(-5, &[0]), solution(-3, &[1]));
+    assert_eq!(five.objective_in(false), int(5));
+    assert!(five.is_better_than(&three));
+    assert!(!three.is_better_than(&five));
+
+    // Ties are broken by the variables, and nothing is better than itself
+    assert!(solution(3, &[0, 1]).is_better_than(&solution(3, &[1, 0])));
+    assert!(!solution(3, &[1]).is_better_than(&solution(3, &[1])));
+}
+
+#[test]
+fn problem_check_big_m_warns_about_a_huge_coefficient() {
+    let problem = prepare_problem();
+    assert_eq!(
+        problem.max_objective_coefficient(),
+        BigRationalExt::from_float(70.)
+    );
+    assert_eq!(problem.check_big_m(&BigM::Symbolic), None);
+    assert_eq!(
+        problem.check_big_m(&BigM::Numeric(BigRationalExt::from_float(1e6))),
+        None
+    );
+
+    let mut problem = problem;
+    problem.set_objective(RowDVector::from_row_slice(&[
+        BigRationalExt::from_float(1.),
+        BigRationalExt::from_float(-1e9),
+    ]));
+    assert_eq!(
+        problem.check_big_m(&BigM::Numeric(BigRationalExt::from_float(1e6))),
+        Some(SolveWarning::BigMTooSmall {
+            big_m: BigRationalExt::from_float(1e6),
+            max_coefficient: BigRationalExt::from_float(1e9),
+        })
+    );
+    assert_eq!(problem.check_big_m(&BigM::Symbolic), None);
+}
+
+#[test]
+fn problem_remove_redundant_artificials_keeps_the_optimum() {
+    let from_floats = |floats: &[f64]| {
+        floats
+            .iter()
+            .copied()
+            .map(BigRationalExt::from_float)
+            .collect::<Vec<_>>()
+    };
+    // max x1 + 2x2 - x3; x1 + x2 <= 4; x1 + 2x2 >= 2; x2 + x3 = 3
+    let problem = Problem::from_rows(
+        from_floats(&[1., 2., -1.]),
+        false,
+        vec![
+            (
+                from_floats(&[1., 1., 0.]),
+                Sign::Less,
+                BigRationalExt::from_float(4.),
+            ),
+            (
+                from_floats(&[1., 2., 0.]),
+                Sign::Greater,
+                BigRationalExt::from_float(2.),
+            ),
+            // x3 is the unit column of the equality
+            (
+                from_floats(&[0., 1., 1.]),
+                Sign::Equals,
+                BigRationalExt::from_float(3.),
+            ),
+        ],
+    )
+    .unwrap();
+    let mut reduced = problem.clone();
+    reduced.remove_redundant_artificials();
+
+    assert_eq!(problem.constraints.ncols(), 8);
+    assert_eq!(reduced.constraints.ncols(), 6);
+    assert_eq!(reduced.n_artificial(), 1);
+
+    let expected = problem.solve().unwrap();
+    assert_eq!(
+        expected.vars,
+        vec![
+            BigRational::one(),
+            BigRational::from_integer(3.into()),
+            BigRational::zero()
+        ]
+    );
+    assert_eq!(reduced.solve().unwrap(), expected);
+
+    // Nothing is removed from the ship problem, where every column has several nonzero coefficients
+    let mut problem = prepare_problem();
+    problem.remove_redundant_artificials();
+    assert_eq!(problem, prepare_problem());
+}
+
+#[test]
+fn problem_solve_with_whole_skips_branching_on_integral_relaxation() {
+    let from_floats = |floats: &[f64]| {
+        floats
+            .iter()
+            .copied()
+            .map(BigRationalExt::from_float)
+            .collect::<Vec<_>>()
+    };
+    // Wyndor Glass, whose relaxation is at the integer point (2, 6)
+    let problem = Problem::from_rows(
+        from_floats(&[3., 5.]),
+        false,
+        [([1., 0.], 4.), ([0., 2.], 12.), ([3., 2.], 18.)]
+            .into_iter()
+            .map(|(coefficients, rhs)| {
+                (
+                    from_floats(&coefficients),
+                    Sign::Less,
+                    BigRationalExt::from_float(rhs),
+                )
+            })
+            .collect(),
+    )
+    .unwrap();
+
+    let (solution, stats) = problem.clone().solve_with_whole_and_stats();
+    assert_eq!(solution, problem.solve());
+    assert_eq!(stats.branch_nodes_explored, 0);
+    assert!(stats.iterations > 0);
+}
+
+#[test]
+fn problem_summary_counts_the_variables_by_role() {
+    let problem = prepare_problem();
+    assert_eq!(problem.num_constraints(), 7);
+    assert_eq!(problem.num_variables(), 23);
+    assert_eq!(
+        problem.summary(),
+        "min, 12 vars (4 slack, 7 artificial), 7 constraints"
+    );
+}
+
+#[test]
+fn problem_from_mps_str_solves_to_the_known_optimum() {
+    // Wyndor Glass as the minimization, with x1 <= 4 as the bound
+    // and a redundant ranged row 1 <= x1 + x2 <= 101
+    let mps = "\
+NAME          WYNDOR
+ROWS
+ N  PROFIT
+ L  PLANT2
+ L  PLANT3
+ G  TOTAL
+COLUMNS
+    X1        PROFIT          -3   PLANT3           3
+    X1        TOTAL            1
+    X2        PROFIT          -5   PLANT2           2
+    X2        PLANT3           2   TOTAL            1
+RHS
+    RHS       PLANT2          12   PLANT3          18
+    RHS       TOTAL            1
+RANGES
+    RNG       TOTAL          100
+BOUNDS
+ UP BND       X1               4
+ENDATA
+";
+    let problem = Problem::from_mps_str(mps).unwrap();
+    // Both ends of the range and the bound are constraints of their own
+    assert_eq!(problem.num_constraints(), 5);
+
+    let solution = problem.solve().unwrap();
+    let int = |n: i32| BigRational::from_integer(n.into());
+    assert_eq!(solution.vars, vec![int(2), int(6)]);
+    assert_eq!(solution.objective_in(true), int(-36));
+
+    assert_eq!(
+        Problem::from_mps_str("ROWS\n N  COST\n X  BAD\n").unwrap_err(),
+        "Рядок 3: невідомий тип рядка \"X\""
+    );
+}
+
+#[test]
+fn problem_equivalent_to_ignores_the_order_of_the_compensating_variables() {
+    let constraint = |coefficients: &[f64], sign, rhs| {
+        Constraint::new(
+            ratio_vec_from_floats(coefficients),
+            sign,
+            BigRationalExt::from_float(rhs),
+        )
+    };
+    let objective = || ObjectiveFunction::new(ratio_vec_from_floats(&[3., 5.]), false);
+    let first = constraint(&[1., 0.], Sign::Less, 4.);
+    let second = constraint(&[3., 2.], Sign::Greater, 6.);
+
+    let normalized = Problem::new(objective(), vec![first.clone(), second.clone()]).unwrap();
+    // The compensating variable of an added constraint goes before the others
+    let mut built_up = Problem::new(objective(), vec![first.clone()]).unwrap();
+    built_up.add_constraint(second.clone());
+
+    assert_ne!(normalized, built_up);
+    assert!(normalized.equivalent_to(&built_up));
+    assert!(built_up.equivalent_to(&normalized));
+
+    let mut other_sign = Problem::new(objective(), vec![first]).unwrap();
+    other_sign.add_constraint(constraint(&[3., 2.], Sign::Less, 6.));
+    assert!(!normalized.equivalent_to(&other_sign));
+}
+
+#[test]
+fn problem_presolve_fixes_the_variables_of_the_singleton_equalities() {
+    for minimize in [true, false] {
+        let constraint = |coefficients: &[f64], sign, rhs| {
+            Constraint::new(
+                ratio_vec_from_floats(coefficients),
+                sign,
+                BigRationalExt::from_float(rhs),
+            )
+        };
+        let problem = Problem::new(
+            ObjectiveFunction::new(ratio_vec_from_floats(&[1., 2., 3.]), minimize),
+            vec![
+                constraint(&[1., 1., 1.], Sign::Less, 10.),
+                constraint(&[1., 1., 1.], Sign::Greater, 4.),
+                constraint(&[0., 0., 2.], Sign::Equals, 2.),
+                // Becomes a singleton, once `x3` is fixed
+                constraint(&[0., 1., 1.], Sign::Equals, 3.),
+            ],
+        )
+        .unwrap();
+
+        let mut presolved = problem.clone();
+        presolved.presolve();
+        assert_eq!(presolved.num_constraints(), 2);
+        assert_eq!(presolved.n_artificial(), 2);
+        assert_eq!(presolved.n_original(), 3);
+
+        let expected = problem.solve().unwrap();
+        let solution = presolved.solve_ref().unwrap();
+        assert_eq!(solution, expected);
+        let int = |n: i32| BigRational::from_integer(n.into());
+        assert_eq!(solution.vars[1..], [int(2), int(1)]);
+        assert_eq!(
+            solution.objective_in(minimize),
+            if minimize { int(8) } else { int(14) }
+        );
+        assert_eq!(presolved.verify(&solution), Ok(()));
+        assert_eq!(step_to_optimum(presolved.clone()), expected);
+        assert_eq!(presolved.solve_with_whole().unwrap(), expected);
+    }
+}
+
+#[test]
+fn problem_i128_backend_agrees_with_big_int() {
+    // max 3x1 + 5x2; x1 <= 4; 3x1 + 2x2 <= 18
+    let maximization = Problem::new(
+        ObjectiveFunction::new(ratio_vec_from_floats(&[3., 5.]), false),
+        vec![
+            Constraint::new(
+                ratio_vec_from_floats(&[1., 0.]),
+                Sign::Less,
+                BigRationalExt::from_float(4.),
+            ),
+            Constraint::new(
+                ratio_vec_from_floats(&[3., 2.]),
+                Sign::Less,
+                BigRationalExt::from_float(18.),
+            ),
+        ],
+    )
+    .unwrap();
+    for problem in [
+        prepare_problem(),
+        prepare_beale_problem().with_lexicographic_ratio_test(true),
+        maximization,
+    ] {
+        let fast = problem.clone().with_numeric_backend(NumericBackend::I128);
+        // Solved without the fallback
+        assert_eq!(fast.solve_i128(), Some(problem.solve_ref()));
+        assert_eq!(fast.solve(), problem.solve());
+    }
+    let problem = prepare_problem();
+    assert_eq!(
+        problem
+            .clone()
+            .with_numeric_backend(NumericBackend::I128)
+            .solve_with_whole(),
+        problem.solve_with_whole()
+    );
+}
+
+#[test]
+fn problem_i128_backend_falls_back_to_big_int_on_overflow() {
+    let power = |exp: u32| BigRationalExt::from_integer(BigInt::from(2).pow(exp));
+    let int = |n: i32| BigRationalExt::from_integer(n.into());
+    // max x1 + x2; (2^120 + 1)x1 + 3x2 <= 2^120; 5x1 + (2^120 - 1)x2 <= 7.
+    // Every value fits into i128, but the first pivot overflows
+    let overflowing = Problem::from_rows(
+        vec![int(1), int(1)],
+        false,
+        vec![
+            (vec![&power(120) + &int(1), int(3)], Sign::Less, power(120)),
+            (vec![int(5), &power(120) - &int(1)], Sign::Less, int(7)),
+        ],
+    )
+    .unwrap();
+    // 2^130 doesn't fit from the start
+    let too_big = Problem::from_rows(
+        vec![int(1), int(1)],
+        false,
+        vec![(vec![int(1), int(1)], Sign::Less, power(130))],
+    )
+    .unwrap();
+
+    for problem in [overflowing, too_big] {
+        let fast = problem.clone().with_numeric_backend(NumericBackend::I128);
+        assert_eq!(fast.solve_i128(), None);
+        let solution = fast.solve_ref();
+        assert!(solution.is_ok());
+        assert_eq!(solution, problem.solve());
+    }
+}
+
+#[test]
+fn sign_display() {
+    assert_eq!(Sign::Less.to_string(), "<=");
+    assert_eq!(Sign::Equals.to_string(), "=");
+    assert_eq!(Sign::Greater.to_string(), ">=");
+}
+
+#[test]
+fn problem_solve_all_optima_returns_every_optimal_vertex() {
+    // max x1 + x2; x1 + x2 <= 4; x1 <= 3; x2 <= 3 is optimal on the edge from (3, 1) to (1, 3)
+    let constraint = |coefficients: &[f64], rhs| {
+        Constraint::new(
+            ratio_vec_from_floats(coefficients),
+            Sign::Less,
+            BigRationalExt::from_float(rhs),
+        )
+    };
+    let problem = Problem::new(
+        ObjectiveFunction::new(ratio_vec_from_floats(&[1., 1.]), false),
+        vec![
+            constraint(&[1., 1.], 4.),
+            constraint(&[1., 0.], 3.),
+            constraint(&[0., 1.], 3.),
+        ],
+    )
+    .unwrap();
+
+    let int = |n: i32| BigRational::from_integer(n.into());
+    let optima = problem.clone().solve_all_optima(10).unwrap();
+    assert_eq!(optima.len(), 2);
+    assert_eq!(optima[0], problem.clone().solve().unwrap());
+    for vars in [vec![int(3), int(1)], vec![int(1), int(3)]] {
+        assert!(optima.iter().any(|optimum| optimum.vars == vars));
+    }
+    assert!(optima
+        .iter()
+        .all(|optimum| optimum.objective_in(false) == int(4)));
+
+    assert_eq!(problem.clone().solve_all_optima(1).unwrap().len(), 1);
+    // The unique optimum has no alternatives
+    let mut unique = problem;
+    unique.set_objective(ratio_vec_from_floats(&[2., 1.]));
+    assert_eq!(unique.solve_all_optima(10).unwrap().len(), 1);
+}
+
+#[test]
+fn problem_objective_value_at_the_solution_is_its_fn_val() {
+    let point = |solution: &Solution| {
+        solution
+            .vars
+            .iter()
+            .cloned()
+            .map(BigRationalExt::from)
+            .collect::<Vec<_>>()
+    };
+
+    let problem = prepare_problem();
+    let solution = problem.solve_ref().unwrap();
+    assert_eq!(
+        problem.objective_value_at(&point(&solution)),
+        BigRationalExt::from(solution.fn_val)
+    );
+
+    // max 3x1 + 5x2; x1 <= 4; 3x1 + 2x2 <= 18, scaled by a fraction
+    let mut maximization = Problem::new(
+        ObjectiveFunction::new(ratio_vec_from_floats(&[0.75, 1.25]), false),
+        vec![
+            Constraint::new(
+                ratio_vec_from_floats(&[1., 0.]),
+                Sign::Less,
+                BigRationalExt::from_float(4.),
+            ),
+            Constraint::new(
+                ratio_vec_from_floats(&[3., 2.]),
+                Sign::Less,
+                BigRationalExt::from_float(18.),
+            ),
+        ],
+    )
+    .unwrap();
+    maximization.scale_to_integer_coefficients();
+    let solution = maximization.solve_ref().unwrap();
+    assert_eq!(
+        maximization.objective_value_at(&point(&solution)),
+        BigRationalExt::from(solution.objective_in(false))
+    );
+    assert_eq!(
+        maximization.objective_value_at(ratio_vec_from_floats(&[4., 0.]).as_slice()),
+        BigRationalExt::from_float(3.)
+    );
+}
+
+#[test]
+fn problem_add_constraint_agrees_with_the_problem_built_from_scratch() {
+    let original = prepare_problem();
+    let n_original = original.n_original();
+    let added = Constraint::new(
+        ratio_vec_from_floats(&[1., 0., 0., 1.]),
+        Sign::Less,
+        BigRationalExt::from_float(10.),
+    );
+
+    let mut problem = original.clone();
+    problem.add_constraint(added.clone());
+
+    let mut constraints = (0..original.constraints.nrows())
+        .map(|row| {
+            Constraint::new(
+                original
+                    .constraints
+                    .row(row)
+                    .columns(0, n_original)
+                    .into_owned(),
+                original.constraint_sign(row),
+                original.rhs[row].clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    constraints.push(added);
+    let objective = ObjectiveFunction::new(
+        original
+            .objective_function
+            .coefficients
+            .columns(0, n_original)
+            .map(|coefficient| BigRationalExt::try_from(coefficient).unwrap()),
+        true,
+    );
+    let from_scratch = Problem::new(objective, constraints).unwrap();
+    assert!(problem.equivalent_to(&from_scratch));
+
+    let solution = problem.clone().solve().unwrap();
+    assert_eq!(solution, from_scratch.solve().unwrap());
+    assert_eq!(problem.verify(&solution), Ok(()));
+    // The added constraint cuts off the previous optimum
+    assert_ne!(solution.fn_val, original.solve().unwrap().fn_val);
+}
+
+#[test]
+fn problem_equilibrate_keeps_the_optimum_in_the_original_units() {
+    let ratio = |n: i64, d: i64| BigRationalExt::from((n.into(), d.into()));
+    let row = |coefficients: [BigRationalExt; 2]| RowDVector::from_vec(coefficients.to_vec());
+    // The example of Wyndor Glass Co. for `x1 / 1000` and `1000 * x2`
+    let problem = Problem::new(
+        ObjectiveFunction::new(row([ratio(3000, 1), ratio(1, 200)]), false),
+        vec![
+            Constraint::new(row([ratio(1000, 1), ratio(0, 1)]), Sign::Less, ratio(4, 1)),
+            Constraint::new(row([ratio(0, 1), ratio(1, 500)]), Sign::Less, ratio(12, 1)),
+            Constraint::new(
+                row([ratio(3000, 1), ratio(1, 500)]),
+                Sign::Less,
+                ratio(18, 1),
+            ),
+        ],
+    )
+    .unwrap();
+    let mut equilibrated = problem.clone();
+    let scales = equilibrated.equilibrate();
+    assert_eq!(scales, vec![ratio(1, 3000), ratio(500, 1)]);
+    assert!(equilibrated
+        .constraints
+        .columns(0, 2)
+        .iter()
+        .all(|el| el <= &BigRationalExt::one()));
+
+    let solution = equilibrated.solve_ref().unwrap();
+    assert_eq!(solution, problem.solve_ref().unwrap());
+    assert_eq!(
+        solution.vars,
+        vec![
+            BigRational::new(1.into(), 500.into()),
+            BigRational::from_integer(6000.into())
+        ]
+    );
+    assert_eq!(solution.fn_val, BigRational::from_integer((-36).into()));
+    assert_eq!(equilibrated.verify(&solution), Ok(()));
+    assert_eq!(
+        equilibrated.objective_value_at(&[ratio(1, 500), ratio(6000, 1)]),
+        ratio(36, 1)
+    );
+
+    // The branches bound the variables in the original units
+    let mut bounded = equilibrated.clone();
+    bounded.add_constraint_on_var(1, Sign::Less, ratio(5000, 1));
+    let solution = bounded.solve().unwrap();
+    assert_eq!(
+        solution.vars,
+        vec![
+            BigRational::new(1.into(), 375.into()),
+            BigRational::from_integer(5000.into())
+        ]
+    );
+    assert_eq!(solution.fn_val, BigRational::from_integer((-33).into()));
+}
+
+#[test]
+fn problem_solve_with_basis_reports_a_feasible_basis() {
+    let problem = prepare_problem();
+
+    let (solution, basis) = problem.solve_with_basis().unwrap();
+
+    assert_eq!(solution, problem.solve_ref().unwrap());
+    assert_eq!(basis.len(), problem.num_constraints());
+    let mut indices = basis.iter().map(|(i, _)| *i).collect::<Vec<_>>();
+    indices.sort_unstable();
+    indices.dedup();
+    assert_eq!(indices.len(), basis.len());
+    for (i, role) in &basis {
+        assert_eq!(problem.variable_role(*i), Some(*role));
+        // The optimum is feasible, so the artificial variables have left the basis
+        assert_ne!(*role, VariableRole::Artificial);
+    }
+    // The non-basic variables are zero, while the basic ones satisfy the constraints
+    for (j, var) in solution.vars.iter().enumerate() {
+        assert!(indices.contains(&j) || var.is_zero(), "x{} = {var}", j + 1);
+    }
+    assert_eq!(problem.verify(&solution), Ok(()));
+}
+
+#[test]
+fn problem_is_feasible_and_is_bounded() {
+    let constraint = |coefficients: &[f64], sign, rhs| {
+        Constraint::new(
+            ratio_vec_from_floats(coefficients),
+            sign,
+            BigRationalExt::from_float(rhs),
+        )
+    };
+    let wyndor = Problem::new(
+        ObjectiveFunction::new(ratio_vec_from_floats(&[3., 5.]), false),
+        vec![
+            constraint(&[1., 0.], Sign::Less, 4.),
+            constraint(&[0., 2.], Sign::Less, 12.),
+            constraint(&[3., 2.], Sign::Less, 18.),
+        ],
+    )
+    .unwrap();
+    assert!(wyndor.is_feasible());
+    assert!(wyndor.is_bounded());
+
+    let infeasible = Problem::new(
+        ObjectiveFunction::new(ratio_vec_from_floats(&[1., 1.]), true),
+        vec![
+            constraint(&[1., 1.], Sign::Less, 1.),
+            constraint(&[1., 1.], Sign::Greater, 2.),
+        ],
+    )
+    .unwrap();
+    assert!(!infeasible.is_feasible());
+    assert!(infeasible.is_bounded());
+
+    // `x1` grows along `x2 = x1 - 1` without a limit
+    let unbounded = |minimize| {
+        Problem::new(
+            ObjectiveFunction::new(ratio_vec_from_floats(&[1., 1.]), minimize),
+            vec![constraint(&[1., -1.], Sign::Less, 1.)],
+        )
+        .unwrap()
+    };
+    assert!(unbounded(false).is_feasible());
+    assert!(!unbounded(false).is_bounded());
+    // The minimum is at the origin, though the feasible region is unbounded
+    assert!(unbounded(true).is_bounded());
+    assert!(prepare_problem().is_bounded());
+}
+
+#[test]
+fn problem_normalized_in_parallel_equals_the_sequential_one() {
+    let problems = |threshold| {
+        [
+            prepare_problem_input(),
+            (
+                ObjectiveFunction::new(ratio_vec_from_floats(&[1., -2., 0.5]), false),
+                vec![
+                    Constraint::new(
+                        ratio_vec_from_floats(&[1., 1.]),
+                        Sign::Less,
+                        BigRationalExt::from_float(-3.),
+                    ),
+                    Constraint::new(
+                        ratio_vec_from_floats(&[0., 2., -1.]),
+                        Sign::Equals,
+                        BigRationalExt::from_float(-1.),
+                    ),
+                    Constraint::new(
+                        ratio_vec_from_floats(&[4.]),
+                        Sign::Greater,
+                        BigRationalExt::from_float(2.),
+                    ),
+                ],
+            ),
+        ]
+        .map(|(objective_function, constraints)| {
+            Problem::normalize_with_threshold(objective_function, constraints, threshold)
+        })
+    };
+
+    assert_eq!(problems(usize::MAX), problems(0));
+}
+
+#[test]
+fn problem_extended_by_the_ship_model_constraints() {
+    let int = |n: i64| BigRationalExt::from_integer(n.into());
+    let matrix = |values: &[i64]| DMatrix::from_row_iterator(2, 2, values.iter().map(|n| int(*n)));
+    let (objective, constraints) = crate::simplex::build_ship_model(
+        &matrix(&[15, 30, 10, 25]),
+        &matrix(&[15, 70, 20, 23]),
+        &DVector::from_vec(vec![int(300), int(200)]),
+        &RowDVector::from_row_slice(&[50, 20]),
+        true,
+        Sign::Equals,
+    );
+    let empty = || {
+        let mut problem = Problem::empty(objective.coefficients.len(), true);
+        problem.set_objective(objective.coefficients.clone());
+        problem
+    };
+
+    let mut one_by_one = empty();
+    for constraint in constraints.clone() {
+        one_by_one.add_constraint(constraint);
+    }
+    let mut extended = empty();
+    extended.extend(constraints.clone());
+    assert_eq!(extended, one_by_one);
+    assert_eq!(empty().with_constraints(constraints.clone()), one_by_one);
+
+    // The compensating variables of the added constraints are in the reverse order
+    let normalized = Problem::new(objective, constraints).unwrap();
+    assert!(extended.equivalent_to(&normalized));
+    assert_eq!(
+        extended.solve_with_whole().unwrap(),
+        normalized.solve_with_whole().unwrap()
+    );
+}
+
+#[test]
+fn simplex_solver_steps_to_unbounded() {
+    // `x1` grows along `x2 = x1 - 1` without a limit
+    let problem = Problem::new(
+        ObjectiveFunction::new(ratio_vec_from_floats(&[1., 1.]), false),
+        vec![Constraint::new(
+            ratio_vec_from_floats(&[1., -1.]),
+            Sign::Less,
+            BigRationalExt::one(),
+        )],
+    )
+    .unwrap();
+
+    let mut solver = SimplexSolver::new(problem.clone()).unwrap();
+    assert!(matches!(solver.next_step(), StepOutcome::Pivoted { .. }));
+    assert_eq!(solver.next_step(), StepOutcome::Unbounded);
+    assert_eq!(solver.next_step(), StepOutcome::Unbounded);
+    assert_eq!(problem.solve(), Err(SolutionError::Infinite));
+}
+
+/// Steps the solver of the `problem` to the end, that has to be optimal
+fn step_to_optimum(problem: Problem) -> Solution {
+    let mut solver = SimplexSolver::new(problem).unwrap();
+    loop {
+        match solver.next_step() {
+            StepOutcome::Pivoted { .. } => {}
+            StepOutcome::Optimal(solution) => break solution,
+            outcome => panic!("Unexpected outcome: {outcome:?}"),
+        }
+    }
+}
+
+#[test]
+fn simplex_solver_unscales_the_objective() {
+    // max 3x1 + 5x2; x1 <= 4; 3x1 + 2x2 <= 18, scaled by a fraction
+    let mut problem = Problem::new(
+        ObjectiveFunction::new(ratio_vec_from_floats(&[0.75, 1.25]), false),
+        vec![
+            Constraint::new(
+                ratio_vec_from_floats(&[1., 0.]),
+                Sign::Less,
+                BigRationalExt::from_float(4.),
+            ),
+            Constraint::new(
+                ratio_vec_from_floats(&[3., 2.]),
+                Sign::Less,
+                BigRationalExt::from_float(18.),
+            ),
+        ],
+    )
+    .unwrap();
+    problem.scale_to_integer_coefficients();
+
+    let solution = step_to_optimum(problem.clone());
+    assert_eq!(solution, problem.solve().unwrap());
+    assert_eq!(
+        solution.objective_in(false),
+        BigRational::new(45.into(), 4.into())
+    );
+}
+
+#[test]
+fn problem_i128_backend_finishes_the_unbounded_problem() {
+    // `x1` grows along `x2 = x1 - 1` without a limit
+    let problem = Problem::new(
+        ObjectiveFunction::new(ratio_vec_from_floats(&[1., 1.]), false),
+        vec![Constraint::new(
+            ratio_vec_from_floats(&[1., -1.]),
+            Sign::Less,
+            BigRationalExt::one(),
+        )],
+    )
+    .unwrap()
+    .with_numeric_backend(NumericBackend::I128);
+
+    // Not an overflow, that would fall back to `BigInt`
+    assert_eq!(problem.solve_i128(), Some(Err(SolutionError::Infinite)));
+    assert_eq!(problem.solve(), Err(SolutionError::Infinite));
+}
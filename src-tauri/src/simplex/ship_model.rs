@@ -0,0 +1,161 @@
+use nalgebra::{DMatrix, DVector, RowDVector};
+use num_traits::{FromPrimitive, One, Zero};
+use ratio_extension::BigRationalExt;
+
+use super::{Constraint, ObjectiveFunction, Sign};
+
+/// Model of the ships distribution over the lines.
+///
+/// The variable `n_ij`, the number of the ships of the type `j` on the line `i`,
+/// has the index `i * n_ships + j`. Every line gets the constraint `sum_j a_ij * n_ij >= b_i`,
+/// then every ship type gets `sum_i n_ij ships_count_sign N_j`.
+/// The infinite cost marks an unavailable ship-line pair: instead of carrying it through the tableau,
+/// its variable costs nothing and is fixed to zero by an extra constraint
+pub fn build_ship_model(
+    transport_rate: &DMatrix<BigRationalExt>,
+    cost_rate: &DMatrix<BigRationalExt>,
+    min_transport_per_line: &DVector<BigRationalExt>,
+    ships_count_per_type: &RowDVector<u16>,
+    minimize: bool,
+    ships_count_sign: Sign,
+) -> (ObjectiveFunction<BigRationalExt>, Vec<Constraint>) {
+    let (n_lines, n_ships) = transport_rate.shape();
+    let n_variables = n_lines * n_ships;
+
+    let coefficients = DMatrix::from_fn(n_lines + n_ships, n_variables, |r, k| {
+        let (i, j) = (k / n_ships, k % n_ships);
+        match r.checked_sub(n_lines) {
+            None if r == i => transport_rate[(i, j)].clone(),
+            Some(ship) if ship == j => BigRationalExt::one(),
+            _ => BigRationalExt::zero(),
+        }
+    });
+    let mut constraints = coefficients
+        .row_iter()
+        .zip(
+            min_transport_per_line
+                .iter()
+                .map(|rhs| (Sign::Greater, rhs.clone())),
+        )
+        .chain(
+            coefficients.row_iter().skip(n_lines).zip(
+                ships_count_per_type
+                    .iter()
+                    .map(|count| (ships_count_sign, BigRationalExt::from_u16(*count).unwrap())),
+            ),
+        )
+        .map(|(row, (sign, rhs))| Constraint::new(row.into_owned(), sign, rhs))
+        .collect::<Vec<_>>();
+
+    let objective = RowDVector::from_iterator(
+        n_variables,
+        // The row-major order of the variables
+        cost_rate
+            .transpose()
+            .into_iter()
+            .cloned()
+            .enumerate()
+            .map(|(k, cost)| {
+                if cost.is_finite() {
+                    return cost;
+                }
+                let mut fixed = RowDVector::zeros(n_variables);
+                fixed[k] = One::one();
+                constraints.push(Constraint::new(fixed, Sign::Equals, Zero::zero()));
+                Zero::zero()
+            }),
+    );
+
+    (ObjectiveFunction::new(objective, minimize), constraints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(n: i64) -> BigRationalExt {
+        BigRationalExt::from_i64(n).unwrap()
+    }
+
+    fn matrix(nrows: usize, ncols: usize, values: &[i64]) -> DMatrix<BigRationalExt> {
+        DMatrix::from_row_iterator(nrows, ncols, values.iter().map(|n| int(*n)))
+    }
+
+    #[test]
+    fn build_ship_model_of_two_lines_and_two_ships() {
+        let (objective, constraints) = build_ship_model(
+            &matrix(2, 2, &[10, 20, 30, 40]),
+            &matrix(2, 2, &[1, 2, 3, 4]),
+            &DVector::from_vec(vec![int(100), int(200)]),
+            &RowDVector::from_row_slice(&[5, 6]),
+            true,
+            Sign::Equals,
+        );
+
+        assert_eq!(
+            objective,
+            ObjectiveFunction::new(matrix(1, 4, &[1, 2, 3, 4]).row(0).into_owned(), true)
+        );
+        let expected = matrix(
+            4,
+            4,
+            &[
+                10, 20, 0, 0, //
+                0, 0, 30, 40, //
+                1, 0, 1, 0, //
+                0, 1, 0, 1,
+            ],
+        );
+        assert_eq!(
+            constraints,
+            expected
+                .row_iter()
+                .zip([
+                    (Sign::Greater, int(100)),
+                    (Sign::Greater, int(200)),
+                    (Sign::Equals, int(5)),
+                    (Sign::Equals, int(6)),
+                ])
+                .map(|(row, (sign, rhs))| Constraint::new(row.into_owned(), sign, rhs))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn build_ship_model_fixes_the_unavailable_pairs_to_zero() {
+        let mut cost_rate = matrix(2, 2, &[1, 2, 3, 4]);
+        cost_rate[(1, 0)] = BigRationalExt::Inf;
+        let (objective, constraints) = build_ship_model(
+            &matrix(2, 2, &[10, 20, 30, 40]),
+            &cost_rate,
+            &DVector::zeros(2),
+            &RowDVector::from_row_slice(&[5, 6]),
+            false,
+            Sign::Less,
+        );
+
+        assert_eq!(
+            objective.coefficients,
+            matrix(1, 4, &[1, 2, 0, 4]).row(0).into_owned()
+        );
+        assert!(!objective.minimization);
+        assert_eq!(
+            constraints.iter().map(Constraint::sign).collect::<Vec<_>>(),
+            [
+                Sign::Greater,
+                Sign::Greater,
+                Sign::Less,
+                Sign::Less,
+                Sign::Equals
+            ]
+        );
+        assert_eq!(
+            constraints[4],
+            Constraint::new(
+                matrix(1, 4, &[0, 0, 1, 0]).row(0).into_owned(),
+                Sign::Equals,
+                int(0)
+            )
+        );
+    }
+}
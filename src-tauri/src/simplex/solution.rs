@@ -1,13 +1,78 @@
-use std::fmt;
+use std::{cmp, fmt, ops::Index, slice, vec};
 
 use num_rational::BigRational;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq)]
+use super::ProblemError;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Solution {
+    /// Value of the objective function, as it was minimized by the solver.
+    /// For a maximization problem it is the negated value of the user's objective,
+    /// use [`Solution::objective_in`] to get the value in the user's terms
     pub fn_val: BigRational,
     pub vars: Vec<BigRational>,
 }
 
+impl Solution {
+    /// Value of the objective function in the sense of the problem, that was solved
+    pub fn objective_in(&self, minimization: bool) -> BigRational {
+        if minimization {
+            self.fn_val.clone()
+        } else {
+            -self.fn_val.clone()
+        }
+    }
+
+    /// Whether the solution should replace the `other` one as the optimum.
+    ///
+    /// [`Solution::fn_val`] is always minimized, so the sense of the problem is not needed.
+    /// Equal objective values are resolved by the lexicographic order of the variables,
+    /// so that the reported solution does not depend on the order in which branches finish.
+    /// An absent or infinite solution is a [`SolutionError`], which any solution is better than
+    pub fn is_better_than(&self, other: &Solution) -> bool {
+        match self.fn_val.cmp(&other.fn_val) {
+            cmp::Ordering::Equal => self.vars < other.vars,
+            ordering => ordering.is_lt(),
+        }
+    }
+
+    /// Values of the variables in their order, same as [`Solution::vars`]
+    pub fn iter_vars(&self) -> slice::Iter<'_, BigRational> {
+        self.vars.iter()
+    }
+}
+
+/// Value of the variable with the index.
+///
+/// # Panics
+/// If there is no such variable
+impl Index<usize> for Solution {
+    type Output = BigRational;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.vars[index]
+    }
+}
+
+impl IntoIterator for Solution {
+    type Item = BigRational;
+    type IntoIter = vec::IntoIter<BigRational>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.vars.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Solution {
+    type Item = &'a BigRational;
+    type IntoIter = slice::Iter<'a, BigRational>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_vars()
+    }
+}
+
 pub type SolutionResult = Result<Solution, SolutionError>;
 
 #[derive(
@@ -25,6 +90,18 @@ pub enum SolutionError {
     Infinite,
     #[display(fmt = "Розв'язок відсутній")]
     Absent,
+    #[display(fmt = "Перевищено максимальну кількість ітерацій")]
+    IterationLimit,
+    #[display(fmt = "Обчислення скасовано")]
+    Cancelled,
+    #[display(fmt = "{_0}")]
+    Problem(ProblemError),
+}
+
+impl From<ProblemError> for SolutionError {
+    fn from(err: ProblemError) -> Self {
+        Self::Problem(err)
+    }
 }
 
 impl fmt::Display for Solution {
@@ -0,0 +1,95 @@
+use std::sync::Mutex;
+
+use nalgebra::DMatrix;
+use num_rational::BigRational;
+use ratio_extension::BigRationalExt;
+
+use super::{
+    log_event, problem::Presolved, Problem, ProblemError, SimplexTable, Solution, SolutionError,
+    SolveEvent,
+};
+
+/// Result of a single [`SimplexSolver::next_step`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepOutcome {
+    /// The basis was changed, the solver can be stepped further
+    Pivoted {
+        row: usize,
+        col: usize,
+    },
+    Optimal(Solution),
+    /// The solution is infinite, see [`SolutionError::Infinite`]
+    Unbounded,
+    /// The same pivot column was chosen twice in a row, see [`SolutionError::Absent`]
+    Cycled,
+}
+
+/// Solves a [`Problem`] one pivot at a time, so that the caller can show the tableau between the steps.
+///
+/// Once a final [`StepOutcome`] is reached, every further step returns it again
+#[derive(Debug, Clone)]
+pub struct SimplexSolver {
+    table: SimplexTable,
+    /// See [`Problem::scale_to_integer_coefficients`], the `fn_val` of the optimum is divided by it back
+    objective_scale: BigRational,
+    /// See [`Problem::presolve`], the fixed variables are put back into the optimum
+    presolved: Presolved,
+    prev_pivot_col: Option<usize>,
+    outcome: Option<StepOutcome>,
+}
+
+impl SimplexSolver {
+    /// Fails on the same malformed problems as [`SimplexTable::new`]
+    pub fn new(problem: Problem) -> Result<Self, ProblemError> {
+        Ok(Self {
+            objective_scale: problem.objective_scale.clone(),
+            presolved: problem.presolved.clone(),
+            table: SimplexTable::new(problem)?,
+            prev_pivot_col: None,
+            outcome: None,
+        })
+    }
+
+    pub fn next_step(&mut self) -> StepOutcome {
+        if let Some(outcome) = &self.outcome {
+            return outcome.clone();
+        }
+
+        let pivot = Mutex::new(None);
+        let (solution, pivot_col) = self.table.step(self.prev_pivot_col, &|event| {
+            if let SolveEvent::PivotChosen { row, col } = event {
+                *pivot.lock().unwrap() = Some((row, col));
+            }
+            log_event(event)
+        });
+        self.prev_pivot_col = pivot_col;
+
+        let outcome = match solution {
+            None => {
+                let (row, col) = pivot.into_inner().unwrap().unwrap();
+                return StepOutcome::Pivoted { row, col };
+            }
+            Some(Ok(solution)) => StepOutcome::Optimal(self.presolved.restore(Solution {
+                fn_val: solution.fn_val / &self.objective_scale,
+                ..solution
+            })),
+            Some(Err(SolutionError::Infinite)) => StepOutcome::Unbounded,
+            Some(Err(SolutionError::Absent)) => StepOutcome::Cycled,
+            Some(Err(
+                SolutionError::IterationLimit
+                | SolutionError::Cancelled
+                | SolutionError::Problem(_),
+            )) => {
+                unreachable!(
+                    "The table does not limit or cancel the iterations, its basis is valid"
+                )
+            }
+        };
+        self.outcome = Some(outcome.clone());
+        outcome
+    }
+
+    pub fn current_tableau(&self) -> &DMatrix<BigRationalExt> {
+        self.table.tableau()
+    }
+}
@@ -0,0 +1,43 @@
+use ratio_extension::BigRationalExt;
+
+use super::SolveEvent;
+
+/// Informational remark about the solve, that doesn't change its result
+#[derive(Debug, Clone, PartialEq, derive_more::Display)]
+pub enum SolveWarning {
+    /// Pivot element is much smaller than the largest element of its column
+    #[display(fmt = "Small pivot element at ({row}, {col})")]
+    SmallPivot { row: usize, col: usize },
+    /// Several rows were tied in the minimum ratio test
+    #[display(fmt = "{count} rows are tied in the ratio test")]
+    ManyDegenerateTies { count: usize },
+    /// An artificial variable stayed in the basis after the last iteration
+    #[display(fmt = "Artificial variable is in the basis at termination")]
+    ArtificialInBasisAtTermination,
+    /// A numeric big M does not dominate the objective function, see [`Problem::check_big_m`](super::Problem::check_big_m)
+    #[display(fmt = "Big M {big_m} is too small for the objective coefficient {max_coefficient}")]
+    BigMTooSmall {
+        big_m: BigRationalExt,
+        max_coefficient: BigRationalExt,
+    },
+}
+
+/// Summary of a finished solve
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SolveStats {
+    pub iterations: u32,
+    /// Branches of the branch-and-bound, the root relaxation is not counted
+    pub branch_nodes_explored: u32,
+    pub warnings: Vec<SolveWarning>,
+}
+
+impl SolveStats {
+    pub(crate) fn record(&mut self, event: &SolveEvent) {
+        match event {
+            SolveEvent::IterationStarted { .. } => self.iterations += 1,
+            SolveEvent::BranchStarted { .. } => self.branch_nodes_explored += 1,
+            SolveEvent::Warning(warning) => self.warnings.push(warning.clone()),
+            _ => {}
+        }
+    }
+}
@@ -1,33 +1,54 @@
+use std::{cmp, ops::Neg};
+
 use lazy_static::lazy_static;
 use nalgebra::{DMatrix, DVector, DVectorSlice, RowDVector};
 use num_rational::Ratio;
-use num_traits::Zero;
+use num_traits::{One, Zero};
 use ratio_extension::{BigRationalExt, RatioExt};
+#[cfg(feature = "parallel")]
 use rayon::prelude::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use crate::dbg_display;
+use crate::{into_par_iter, par_iter};
 
-use super::{big_number::BigNumber, ObjectiveFunction, Problem, Solution};
-use super::{SolutionError, SolutionResult};
+use super::{
+    big_number::BigNumber, EventSink, ObjectiveFunction, Problem, Solution, SolveEvent,
+    SolveWarning,
+};
+use super::{ProblemError, SolutionError, SolutionResult};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct SimplexTable {
     n_significant_variables: usize,
     /// Indices of basis vectors
     basis: DVector<usize>,
+    /// Columns of the identity matrix before the first step, so the current columns of the basis inverse
+    initial_basis: Vec<usize>,
     /// i_max x j_max table of coefficients from constraints
     tableau: DMatrix<BigRationalExt>,
     rhs: DVector<BigRationalExt>,
+    /// Rhs of the constraints before the first step
+    initial_rhs: DVector<BigRationalExt>,
+    /// Compensating variable of every constraint, [`None`] for the equalities
+    slacks: Vec<Option<usize>>,
+    /// Maximization is solved as the minimization of the negated objective function
     coefficients: RowDVector<BigNumber<BigRationalExt>>,
-    minimization: bool,
+    n_artificial_variables: usize,
+    lexicographic_ratio_test: bool,
 }
 
 lazy_static! {
     static ref ZERO: BigRationalExt = BigRationalExt::zero();
+    static ref ONE: BigRationalExt = BigRationalExt::one();
+    static ref SMALL_PIVOT_RATIO: BigRationalExt = BigRationalExt::from_integer(1_000_000.into());
 }
 
 impl SimplexTable {
-    pub fn new(problem: Problem) -> Self {
+    /// Fails, if the columns of the `one_big` coefficients don't form the identity matrix,
+    /// which [`Problem::normalize`] always builds from the artificial variables.
+    ///
+    /// Constraints without the artificial variable, see [`Problem::remove_redundant_artificials`],
+    /// start from the unit column of an original or a compensating variable
+    pub fn new(problem: Problem) -> Result<Self, ProblemError> {
         let Problem {
             objective_function:
                 ObjectiveFunction {
@@ -37,22 +58,114 @@ impl SimplexTable {
                 },
             constraints,
             rhs,
+            n_artificial_variables,
+            lexicographic_ratio_test,
+            ..
         } = problem;
-        Self {
+        let coefficients = if minimization {
+            coefficients
+        } else {
+            -coefficients
+        };
+        Self::from_parts(
+            n_significant_variables,
+            coefficients,
+            constraints,
+            rhs,
+            n_artificial_variables,
+            lexicographic_ratio_test,
+        )
+    }
+
+    /// Same as [`SimplexTable::new`], but copies only the data, that the pivoting changes, out of the `problem`.
+    ///
+    /// The objective function is copied once, while negated for the maximization
+    pub fn from_ref(problem: &Problem) -> Result<Self, ProblemError> {
+        let ObjectiveFunction {
             n_significant_variables,
-            basis: DVector::from_vec(
-                coefficients
-                    .column_iter()
-                    .enumerate()
-                    .filter_map(|(i, el)| {
-                        (el.x == BigNumber::<BigRationalExt>::one_big()).then_some(i)
+            coefficients,
+            minimization,
+        } = &problem.objective_function;
+        let coefficients = if *minimization {
+            coefficients.clone()
+        } else {
+            coefficients.map(Neg::neg)
+        };
+        Self::from_parts(
+            *n_significant_variables,
+            coefficients,
+            problem.constraints.clone(),
+            problem.rhs.clone(),
+            problem.n_artificial_variables,
+            problem.lexicographic_ratio_test,
+        )
+    }
+
+    /// `coefficients` are the ones of the minimization
+    fn from_parts(
+        n_significant_variables: usize,
+        coefficients: RowDVector<BigNumber<BigRationalExt>>,
+        constraints: DMatrix<BigRationalExt>,
+        rhs: DVector<BigRationalExt>,
+        n_artificial_variables: usize,
+        lexicographic_ratio_test: bool,
+    ) -> Result<Self, ProblemError> {
+        let first_artificial = constraints.ncols() - n_artificial_variables;
+        let mut artificials = coefficients
+            .column_iter()
+            .enumerate()
+            .filter_map(|(i, el)| (el.x == BigNumber::<BigRationalExt>::one_big()).then_some(i))
+            .peekable();
+        let mut initial_basis = (0..constraints.nrows())
+            .filter_map(|k| {
+                artificials
+                    .next_if(|j| !constraints[(k, *j)].is_zero())
+                    .or_else(|| {
+                        (0..first_artificial).find(|j| is_unit_column(constraints.column(*j), k))
                     })
-                    .collect::<Vec<_>>(),
-            ),
+            })
+            .collect::<Vec<_>>();
+        // The rest can only make the basis malformed
+        initial_basis.extend(artificials);
+        let basis = DVector::from_vec(initial_basis.clone());
+        Self::validate_basis(&basis, &constraints)?;
+        let slacks = constraints
+            .row_iter()
+            .map(|row| (n_significant_variables..first_artificial).find(|j| !row[*j].is_zero()))
+            .collect();
+        Ok(Self {
+            n_significant_variables,
+            basis,
+            initial_basis,
             tableau: constraints,
-            coefficients: coefficients.map(BigNumber::<BigRationalExt>::from),
+            // Already converted to the big numbers by the normalization
+            coefficients,
+            initial_rhs: rhs.clone(),
+            slacks,
             rhs,
-            minimization,
+            n_artificial_variables,
+            lexicographic_ratio_test,
+        })
+    }
+
+    /// The `k`-th basis column must be the `k`-th unit vector
+    fn validate_basis(
+        basis: &DVector<usize>,
+        tableau: &DMatrix<BigRationalExt>,
+    ) -> Result<(), ProblemError> {
+        if basis.len() != tableau.nrows() {
+            return Err(ProblemError::BasisSize {
+                expected: tableau.nrows(),
+                found: basis.len(),
+            });
+        }
+        match basis
+            .iter()
+            .enumerate()
+            .find(|(k, column)| !is_unit_column(tableau.column(**column), *k))
+        {
+            Some((_, column)) => Err(ProblemError::NonIdentityBasis { column: *column }),
+            None => Ok(()),
         }
     }
 
@@ -60,6 +173,15 @@ impl SimplexTable {
         (&self.basis).into()
     }
 
+    pub fn tableau(&self) -> &DMatrix<BigRationalExt> {
+        &self.tableau
+    }
+
+    /// Objective function coefficients of the minimization, negated for the maximization problem
+    pub fn coefficients(&self) -> &RowDVector<BigNumber<BigRationalExt>> {
+        &self.coefficients
+    }
+
     pub fn basis_coefficients(&self) -> DVector</* f64 */ BigNumber<BigRationalExt>> {
         self.basis
             .iter()
@@ -69,10 +191,7 @@ impl SimplexTable {
     }
 
     pub fn function_estimation(&self) -> BigNumber<BigRationalExt> {
-        std::mem::take(
-            &mut (dbg_display!(self.basis_coefficients().transpose())
-                * dbg_display!(self.rhs.map(BigNumber::from)))[0],
-        )
+        dot(self.basis_coefficients().iter(), self.rhs.iter())
     }
 
     pub fn column_estimation(&self, index: usize) -> Option<BigNumber<BigRationalExt>> {
@@ -86,12 +205,9 @@ impl SimplexTable {
     /// # Safety
     /// Panics if index is out of bounds
     unsafe fn column_estimation_unchecked(&self, index: usize) -> BigNumber<BigRationalExt> {
-        std::mem::take(
-            &mut (self.basis_coefficients().transpose()
-                * self
-                    .tableau
-                    .column(index)
-                    .map(BigNumber::<BigRationalExt>::from))[0],
+        dot(
+            self.basis_coefficients().iter(),
+            self.tableau.column(index).iter(),
         ) - {
             let column_coef = self.coefficients[index].to_owned();
             log::debug!("Coefficient of column {index}: {column_coef}");
@@ -102,74 +218,45 @@ impl SimplexTable {
     pub fn step(
         &mut self,
         prev_pivot_column: Option<usize>,
+        sink: &EventSink<'_>,
     ) -> (Option<SolutionResult>, Option<usize>) {
         log::debug!("Tableau:{}", self.tableau);
-        if self.minimization {
-            log::info!("Minimization step");
-            self.step_min(prev_pivot_column)
-        } else {
-            log::info!("Maximization step");
-            self.step_max(prev_pivot_column)
-        }
-    }
-
-    fn step_min(
-        &mut self,
-        prev_pivot_column: Option<usize>,
-    ) -> (Option<SolutionResult>, Option<usize>) {
-        let pivot_col: Option<usize> = (0..self.tableau.ncols())
-            .filter_map(|i| {
-                let estimation = unsafe { self.column_estimation_unchecked(i) };
-                log::debug!("{estimation}");
-                (estimation > Zero::zero()).then_some((i, estimation))
-            })
-            .max_by(|(_, es1), (_, es2)| es1.total_cmp(es2))
-            .map(|(i, _)| i);
+        let pivot_col = self.pivot_column();
 
-        log::info!("Pivot column: {pivot_col:?}");
         if pivot_col.is_some() && prev_pivot_column == pivot_col {
             return (Some(Err(SolutionError::Absent)), pivot_col);
         }
 
         match pivot_col {
             Some(pivot_col) => {
-                log::info!("Optimal solution was not found");
+                log::trace!("Optimal solution was not found");
 
-                let pivot_row = self
-                    .tableau
-                    .column(pivot_col)
-                    .row_iter()
-                    .zip(&self.rhs)
-                    .enumerate()
-                    .filter(|(_, (pivot_col_el, _))| &pivot_col_el.x > &ZERO)
-                    .map(|(i, (pivot_col_el, rhs_el))| (i, rhs_el / &pivot_col_el.x))
-                    .min_by(|(_, ratio1), (_, ratio2)| ratio1.partial_cmp(ratio2).unwrap())
-                    .map(|(i, _)| i)
-                    .unwrap();
-                log::info!("Pivot row: {pivot_row}");
+                let Some((pivot_row, n_ties)) = self.pivot_row(pivot_col) else {
+                    log::debug!(
+                        "Column {pivot_col} has no positive elements, the solution is infinite"
+                    );
+                    return (Some(Err(SolutionError::Infinite)), Some(pivot_col));
+                };
+                sink(SolveEvent::PivotChosen {
+                    row: pivot_row,
+                    col: pivot_col,
+                });
+                if n_ties > 1 {
+                    sink(SolveEvent::Warning(SolveWarning::ManyDegenerateTies {
+                        count: n_ties,
+                    }));
+                }
 
                 let pivot_el = self.tableau[(pivot_row, pivot_col)].to_owned();
-                log::info!("Pivot element: {pivot_el}");
-
-                // divide all elements in a row by pivot element
-                self.rhs[pivot_row] /= &pivot_el;
-                self.tableau.row_mut(pivot_row).apply(|el| *el /= &pivot_el);
-
-                // subtract pivot row from other rows till all of elements in pivot coll except of pivot element are zero
-                for i in (0..self.tableau.nrows()).filter(|i| i != &pivot_row) {
-                    let multiplier = self.tableau[(i, pivot_col)].to_owned();
-                    self.rhs[i] = &self.rhs[i] - &(&self.rhs[pivot_row] * &multiplier);
-
-                    let pivot_row = self.tableau.row(pivot_row).into_owned(); // maybe optimize
-                    self.tableau.row_mut(i).zip_apply(
-                        &pivot_row,
-                        |tableau_row_el, pivot_row_el| {
-                            *tableau_row_el -= &pivot_row_el * &multiplier
-                        },
-                    );
+                log::debug!("Pivot element: {pivot_el}");
+                if self.is_small_pivot(pivot_row, pivot_col) {
+                    sink(SolveEvent::Warning(SolveWarning::SmallPivot {
+                        row: pivot_row,
+                        col: pivot_col,
+                    }));
                 }
 
-                self.basis[pivot_row] = pivot_col;
+                self.pivot(pivot_row, pivot_col);
 
                 (None, Some(pivot_col))
             }
@@ -184,39 +271,29 @@ impl SimplexTable {
                         Solution::Absent
                     } else */
                     'b: {
-                        log::info!("Optimal solution was found");
+                        log::debug!("Optimal solution was found");
+                        let first_artificial = self.tableau.ncols() - self.n_artificial_variables;
+                        if self.basis.iter().any(|i| i >= &first_artificial) {
+                            sink(SolveEvent::Warning(
+                                SolveWarning::ArtificialInBasisAtTermination,
+                            ));
+                        }
                         Ok(Solution {
                             vars: {
-                                let vars = (0..self.n_significant_variables)
-                                    .into_par_iter()
-                                    .map(|i| {
-                                        if let Some(k) = self
-                                            .basis
-                                            .iter()
-                                            .enumerate()
-                                            .find_map(|(k, j)| (j == &i).then_some(k))
-                                        {
-                                            self.rhs[k].to_owned()
-                                        } else {
-                                            Zero::zero()
-                                        }
-                                    })
-                                    .collect::<Vec<_>>();
-
-                                if vars
-                                    .par_iter()
+                                let mut vars = self.full_solution();
+                                vars.truncate(self.n_significant_variables);
+
+                                if par_iter!(vars)
                                     .any(|ratio| !matches!(ratio, RatioExt::Finite(_)))
                                 {
                                     break 'b Err(SolutionError::Infinite);
                                 }
 
-                                vars.into_par_iter()
+                                into_par_iter!(vars)
                                     .map(|ratio_ext| unsafe { ratio_ext.finite_unchecked() })
                                     .collect()
                             },
-                            fn_val: match BigRationalExt::try_from(
-                                self.function_estimation(),
-                            ) {
+                            fn_val: match BigRationalExt::try_from(self.function_estimation()) {
                                 Ok(val) => match Ratio::try_from(val) {
                                     Ok(val) => val,
                                     Err(_) => break 'b Err(SolutionError::Infinite),
@@ -234,10 +311,321 @@ impl SimplexTable {
         }
     }
 
-    fn step_max(
-        &mut self,
-        _prev_pivot_column: Option<usize>,
-    ) -> (Option<SolutionResult>, Option<usize>) {
-        unimplemented!()
+    /// Takes the `pivot_col` into the basis instead of the basic variable of the `pivot_row`,
+    /// the same way, as [`step`](Self::step) does, but with the pivot chosen by the caller
+    ///
+    /// # Panics
+    /// If the indices are out of bounds, or the pivot element is zero
+    pub fn pivot(&mut self, pivot_row: usize, pivot_col: usize) {
+        assert!(
+            pivot_row < self.tableau.nrows() && pivot_col < self.tableau.ncols(),
+            "Pivot ({pivot_row}, {pivot_col}) is out of bounds of the {:?} tableau",
+            self.tableau.shape()
+        );
+        let pivot_el = self.tableau[(pivot_row, pivot_col)].to_owned();
+        assert!(!pivot_el.is_zero(), "Pivot element can't be zero");
+
+        // divide all elements in a row by pivot element
+        self.rhs[pivot_row] /= &pivot_el;
+        self.tableau.row_mut(pivot_row).apply(|el| *el /= &pivot_el);
+
+        // subtract pivot row from other rows till all of elements in pivot coll except of pivot element are zero
+        for i in (0..self.tableau.nrows()).filter(|i| i != &pivot_row) {
+            let multiplier = self.tableau[(i, pivot_col)].to_owned();
+            self.rhs[i] = &self.rhs[i] - &(&self.rhs[pivot_row] * &multiplier);
+
+            let pivot_row = self.tableau.row(pivot_row).into_owned(); // maybe optimize
+            self.tableau
+                .row_mut(i)
+                .zip_apply(&pivot_row, |tableau_row_el, pivot_row_el| {
+                    *tableau_row_el -= &pivot_row_el * &multiplier
+                });
+        }
+
+        self.basis[pivot_row] = pivot_col;
+    }
+
+    /// Tables of the optimal vertices, that are adjacent to the current one.
+    ///
+    /// A non-basic column with the zero estimation enters the basis without changing the objective function.
+    /// Such a column without positive elements is a ray of the unbounded optimal set, so it is skipped,
+    /// as well as the artificial columns. Has to be called on the optimal table
+    pub fn alternative_optima(&self) -> Vec<SimplexTable> {
+        let first_artificial = self.tableau.ncols() - self.n_artificial_variables;
+        (0..first_artificial)
+            .filter(|j| !self.basis.iter().any(|i| i == j))
+            .filter(|j| unsafe { self.column_estimation_unchecked(*j) }.is_zero())
+            .filter_map(|j| {
+                let (pivot_row, _) = self.pivot_row(j)?;
+                let mut table = self.clone();
+                table.pivot(pivot_row, j);
+                Some(table)
+            })
+            .collect()
+    }
+
+    /// Value of every column at the current basis: the basic variables take their rhs, the others are zero.
+    ///
+    /// Unlike [`Solution::vars`], keeps all the variables, which are ordered as in the [`Problem`]:
+    /// the original ones first, then the compensating ones, and the artificial ones last,
+    /// see [`Problem::variable_role`]
+    pub fn full_solution(&self) -> Vec<BigRationalExt> {
+        let mut values = vec![BigRationalExt::zero(); self.tableau.ncols()];
+        for (j, value) in self.basis.iter().zip(&self.rhs) {
+            values[*j] = value.clone();
+        }
+        values
+    }
+
+    /// Whether every constraint holds with equality at the current basis:
+    /// either it is an equality, or its compensating variable is zero
+    pub fn binding_constraints(&self) -> Vec<bool> {
+        self.slacks
+            .iter()
+            .map(|slack| match slack {
+                Some(slack) => self
+                    .basis
+                    .iter()
+                    .zip(&self.rhs)
+                    .all(|(j, value)| j != slack || value.is_zero()),
+                None => true,
+            })
+            .collect()
+    }
+
+    /// Interval of the rhs of every constraint, in which the current basis stays feasible, and so optimal.
+    ///
+    /// A change of the `k`-th rhs moves the basic variables along the `k`-th column of the basis inverse,
+    /// which is the current column of the `k`-th vector of the initial basis.
+    /// The bounds refer to the normalized constraints, where the ones with negative rhs are negated
+    pub fn rhs_ranging(&self) -> Vec<(BigRationalExt, BigRationalExt)> {
+        self.initial_basis
+            .iter()
+            .enumerate()
+            .map(|(k, column)| {
+                let (mut lower, mut upper) = (BigRationalExt::MinusInf, BigRationalExt::Inf);
+                for (el, rhs_el) in self.tableau.column(*column).iter().zip(&self.rhs) {
+                    if el.is_zero() {
+                        continue;
+                    }
+                    // The basic variable of the row reaches zero at this change of the rhs
+                    let change = -(rhs_el / el);
+                    if el > &*ZERO {
+                        lower = cmp::max_by(lower, change, BigRationalExt::total_cmp);
+                    } else {
+                        upper = cmp::min_by(upper, change, BigRationalExt::total_cmp);
+                    }
+                }
+                (&self.initial_rhs[k] + &lower, &self.initial_rhs[k] + &upper)
+            })
+            .collect()
+    }
+
+    /// Selects the entering column with the greatest positive estimation.
+    /// Ties are broken by the smallest column index
+    fn pivot_column(&self) -> Option<usize> {
+        (0..self.tableau.ncols())
+            .filter_map(|i| {
+                let estimation = unsafe { self.column_estimation_unchecked(i) };
+                log::debug!("{estimation}");
+                (estimation > *ZERO).then_some((i, estimation))
+            })
+            .max_by(|(i1, es1), (i2, es2)| es1.total_cmp(es2).then_with(|| i2.cmp(i1)))
+            .map(|(i, _)| i)
+    }
+
+    /// Selects the leaving row by the minimum ratio test.
+    /// Also returns the number of rows, that are tied on the minimum ratio.
+    ///
+    /// [`None`], if the column has no positive elements, so the entering variable grows without a limit
+    fn pivot_row(&self, pivot_col: usize) -> Option<(usize, usize)> {
+        let column = self.tableau.column(pivot_col);
+        let candidates = column
+            .iter()
+            .zip(&self.rhs)
+            .enumerate()
+            .filter(|(_, (pivot_col_el, _))| *pivot_col_el > &*ZERO)
+            .collect::<Vec<_>>();
+        let (pivot_row, (pivot_el, rhs_el)) =
+            candidates
+                .iter()
+                .min_by(|(i1, (el1, rhs1)), (i2, (el2, rhs2))| {
+                    let ordering = ratio_cmp(rhs1, el1, rhs2, el2);
+                    if self.lexicographic_ratio_test {
+                        ordering.then_with(|| self.lexicographic_cmp(*i1, *i2, pivot_col))
+                    } else {
+                        ordering
+                    }
+                })?;
+        let n_ties = candidates
+            .iter()
+            .filter(|(_, (el, rhs))| ratio_cmp(rhs, el, rhs_el, pivot_el).is_eq())
+            .count();
+        Some((*pivot_row, n_ties))
+    }
+
+    /// Whether the pivot element is smaller than the largest element of its column
+    /// by more than [`SMALL_PIVOT_RATIO`] times
+    fn is_small_pivot(&self, pivot_row: usize, pivot_col: usize) -> bool {
+        let abs = |el: &BigRationalExt| if el < &*ZERO { -el.clone() } else { el.clone() };
+        let pivot_el = abs(&self.tableau[(pivot_row, pivot_col)]);
+        self.tableau
+            .column(pivot_col)
+            .iter()
+            .any(|el| abs(el) > &pivot_el * &*SMALL_PIVOT_RATIO)
+    }
+
+    /// Compares rows `i1` and `i2`, divided by their elements in the pivot column,
+    /// over the columns of the initial basis.
+    ///
+    /// Those columns started as the identity matrix, so the compared rows are never equal
+    fn lexicographic_cmp(&self, i1: usize, i2: usize, pivot_col: usize) -> cmp::Ordering {
+        let pivot_el1 = &self.tableau[(i1, pivot_col)];
+        let pivot_el2 = &self.tableau[(i2, pivot_col)];
+        self.initial_basis
+            .iter()
+            .map(|j| {
+                (&self.tableau[(i1, *j)] / pivot_el1)
+                    .partial_cmp(&(&self.tableau[(i2, *j)] / pivot_el2))
+                    .unwrap()
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(cmp::Ordering::Equal)
+    }
+}
+
+/// Whether the `column` is the `k`-th unit vector
+pub(super) fn is_unit_column(column: DVectorSlice<BigRationalExt>, k: usize) -> bool {
+    column
+        .iter()
+        .enumerate()
+        .all(|(i, el)| el == if i == k { &*ONE } else { &*ZERO })
+}
+
+/// Compares `rhs1 / el1` with `rhs2 / el2` for positive `el1` and `el2` by the cross-multiplication,
+/// so that the quotients are never constructed
+fn ratio_cmp(
+    rhs1: &BigRationalExt,
+    el1: &BigRationalExt,
+    rhs2: &BigRationalExt,
+    el2: &BigRationalExt,
+) -> cmp::Ordering {
+    (rhs1 * el2).partial_cmp(&(rhs2 * el1)).unwrap()
+}
+
+/// `sum_i coefficients_i * values_i`, as the product of the transposed coefficients by the values
+fn dot<'a>(
+    coefficients: impl IntoIterator<Item = &'a BigNumber<BigRationalExt>>,
+    values: impl IntoIterator<Item = &'a BigRationalExt>,
+) -> BigNumber<BigRationalExt> {
+    coefficients
+        .into_iter()
+        .zip(values)
+        .fold(Zero::zero(), |sum, (coefficient, value)| {
+            sum + coefficient.clone() * BigNumber::from(value.clone())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simplex::{Constraint, Sign};
+
+    use super::*;
+
+    #[test]
+    fn ratio_cmp_agrees_with_division() {
+        let ratio = |n: i64, d: i64| BigRationalExt::from((n.into(), d.into()));
+        // (rhs, el) with many equal ratios
+        let rows = [
+            (ratio(1, 1), ratio(2, 1)),
+            (ratio(2, 1), ratio(4, 1)),
+            (ratio(0, 1), ratio(5, 1)),
+            (ratio(3, 2), ratio(3, 1)),
+            (ratio(0, 1), ratio(1, 3)),
+            (ratio(7, 3), ratio(1, 1)),
+            (ratio(1, 3), ratio(2, 3)),
+        ];
+
+        for (rhs1, el1) in &rows {
+            for (rhs2, el2) in &rows {
+                assert_eq!(
+                    ratio_cmp(rhs1, el1, rhs2, el2),
+                    (rhs1 / el1).partial_cmp(&(rhs2 / el2)).unwrap(),
+                    "{rhs1}/{el1} vs {rhs2}/{el2}"
+                );
+            }
+        }
+
+        let by_division = rows
+            .iter()
+            .enumerate()
+            .min_by(|(_, (rhs1, el1)), (_, (rhs2, el2))| {
+                (rhs1 / el1).partial_cmp(&(rhs2 / el2)).unwrap()
+            })
+            .map(|(i, _)| i);
+        let by_cross_multiplication = rows
+            .iter()
+            .enumerate()
+            .min_by(|(_, (rhs1, el1)), (_, (rhs2, el2))| ratio_cmp(rhs1, el1, rhs2, el2))
+            .map(|(i, _)| i);
+        assert_eq!(by_cross_multiplication, by_division);
+        assert_eq!(by_division, Some(2));
+    }
+
+    #[test]
+    fn dot_agrees_with_the_matrix_product() {
+        let ratio = |n: i64, d: i64| BigRationalExt::from((n.into(), d.into()));
+        let coefficients = DVector::from_vec(vec![
+            BigNumber::new(ratio(1, 1), ratio(-3, 2)),
+            BigNumber::from(ratio(5, 7)),
+            BigNumber::new(ratio(-2, 1), Zero::zero()),
+            Zero::zero(),
+        ]);
+        let values = DVector::from_vec(vec![ratio(2, 3), ratio(-7, 5), ratio(1, 4), ratio(9, 1)]);
+
+        let product = coefficients.transpose() * values.map(BigNumber::from);
+        assert_eq!(dot(coefficients.iter(), values.iter()), product[0]);
+        assert_eq!(
+            dot(coefficients.iter(), values.iter()),
+            BigNumber::new(ratio(1, 6), ratio(-2, 1))
+        );
+        assert!(dot([], []).is_zero());
+    }
+
+    #[test]
+    fn pivot_matches_the_hand_computed_table() {
+        let ratio = |n: i64, d: i64| BigRationalExt::from((n.into(), d.into()));
+        let row = |coefficients: [i64; 2]| {
+            RowDVector::from_iterator(2, coefficients.map(|c| ratio(c, 1)))
+        };
+        let mut problem = Problem::new(
+            ObjectiveFunction::new(row([3, 5]), false),
+            vec![
+                Constraint::new(row([1, 0]), Sign::Less, ratio(4, 1)),
+                Constraint::new(row([0, 2]), Sign::Less, ratio(12, 1)),
+                Constraint::new(row([3, 2]), Sign::Less, ratio(18, 1)),
+            ],
+        )
+        .unwrap();
+        problem.remove_redundant_artificials();
+        let mut table = SimplexTable::new(problem).unwrap();
+
+        table.pivot(1, 1);
+
+        #[rustfmt::skip]
+        let tableau = [
+            (1, 1), (0, 1), (1, 1), (0, 1), (0, 1),
+            (0, 1), (1, 1), (0, 1), (1, 2), (0, 1),
+            (3, 1), (0, 1), (0, 1), (-1, 1), (1, 1),
+        ];
+        assert_eq!(
+            table.tableau,
+            DMatrix::from_row_slice(3, 5, &tableau.map(|(n, d)| ratio(n, d)))
+        );
+        assert_eq!(
+            table.rhs,
+            DVector::from_vec(vec![ratio(4, 1), ratio(6, 1), ratio(6, 1)])
+        );
+        assert_eq!(table.basis, DVector::from_vec(vec![2, 1, 4]));
     }
 }
@@ -0,0 +1,274 @@
+use std::cmp;
+
+use nalgebra::{DMatrix, DVectorSlice};
+use num_bigint::BigInt;
+use num_rational::{BigRational, Ratio};
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One, ToPrimitive, Zero};
+use ratio_extension::BigRationalExt;
+
+use super::{CancellationToken, Problem, Solution, SolutionError, SolutionResult};
+
+type Rational128 = Ratio<i128>;
+
+/// `(big_part, small_part)` of the Big-M number, compared in the same way as the [`BigNumber`](super::big_number::BigNumber)
+type BigNumber128 = (Rational128, Rational128);
+
+/// [`SimplexTable`](super::SimplexTable) of the `i128` fractions, where every operation is checked.
+///
+/// Pivots by the same rules, so it reaches the same basis, as the `BigInt` table.
+/// Every method returns [`None`] on the overflow, then the solve has to start over with the `BigInt` table
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct SimplexTable128 {
+    n_significant_variables: usize,
+    basis: Vec<usize>,
+    initial_basis: Vec<usize>,
+    tableau: DMatrix<Rational128>,
+    rhs: Vec<Rational128>,
+    /// Coefficients of the minimization, like in the [`SimplexTable`](super::SimplexTable)
+    coefficients: Vec<BigNumber128>,
+    lexicographic_ratio_test: bool,
+}
+
+impl SimplexTable128 {
+    /// [`None`], if a value of the `problem` doesn't fit into `i128`, or the initial basis is malformed
+    pub(super) fn from_problem(problem: &Problem) -> Option<Self> {
+        let tableau = DMatrix::from_vec(
+            problem.constraints.nrows(),
+            problem.constraints.ncols(),
+            problem
+                .constraints
+                .iter()
+                .map(to_rational128)
+                .collect::<Option<Vec<_>>>()?,
+        );
+        let rhs = problem
+            .rhs
+            .iter()
+            .map(to_rational128)
+            .collect::<Option<Vec<_>>>()?;
+        let minimization = problem.objective_function.minimization;
+        let coefficients = problem
+            .objective_function
+            .coefficients
+            .iter()
+            .map(|coefficient| {
+                let (big, small) = (
+                    to_rational128(coefficient.big_part())?,
+                    to_rational128(coefficient.small_part())?,
+                );
+                if minimization {
+                    Some((big, small))
+                } else {
+                    Some((neg(&big)?, neg(&small)?))
+                }
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let first_artificial = tableau.ncols() - problem.n_artificial_variables;
+        let one_big = (Rational128::one(), Rational128::zero());
+        let mut artificials = coefficients
+            .iter()
+            .enumerate()
+            .filter_map(|(i, coefficient)| (coefficient == &one_big).then_some(i))
+            .peekable();
+        let mut initial_basis = (0..tableau.nrows())
+            .filter_map(|k| {
+                artificials
+                    .next_if(|j| !tableau[(k, *j)].is_zero())
+                    .or_else(|| {
+                        (0..first_artificial).find(|j| is_unit_column(tableau.column(*j), k))
+                    })
+            })
+            .collect::<Vec<_>>();
+        initial_basis.extend(artificials);
+        // The `BigInt` table reports the malformed basis
+        if initial_basis.len() != tableau.nrows()
+            || initial_basis
+                .iter()
+                .enumerate()
+                .any(|(k, column)| !is_unit_column(tableau.column(*column), k))
+        {
+            return None;
+        }
+
+        Some(Self {
+            n_significant_variables: problem.objective_function.n_significant_variables,
+            basis: initial_basis.clone(),
+            initial_basis,
+            tableau,
+            rhs,
+            coefficients,
+            lexicographic_ratio_test: problem.lexicographic_ratio_test,
+        })
+    }
+
+    /// Steps to the end, like [`Problem::solve`], but without the [`SolveEvent`](super::SolveEvent)s.
+    /// `fn_val` is the one of the table, not yet divided by the objective scale
+    pub(super) fn solve(
+        mut self,
+        max_iterations: Option<u32>,
+        cancellation: Option<&CancellationToken>,
+    ) -> Option<SolutionResult> {
+        let mut prev_pivot_col = None;
+        for iteration in 1u32.. {
+            if matches!(cancellation, Some(token) if token.is_cancelled()) {
+                return Some(Err(SolutionError::Cancelled));
+            }
+            if matches!(max_iterations, Some(max) if iteration > max) {
+                return Some(Err(SolutionError::IterationLimit));
+            }
+
+            let Some(pivot_col) = self.pivot_column()? else {
+                return self.solution();
+            };
+            if prev_pivot_col == Some(pivot_col) {
+                return Some(Err(SolutionError::Absent));
+            }
+            let Some(pivot_row) = self.pivot_row(pivot_col)? else {
+                return Some(Err(SolutionError::Infinite));
+            };
+            self.pivot(pivot_row, pivot_col)?;
+            prev_pivot_col = Some(pivot_col);
+        }
+        unreachable!("The iterations are counted by `u32`")
+    }
+
+    fn solution(&self) -> Option<SolutionResult> {
+        let mut vars = vec![Rational128::zero(); self.n_significant_variables];
+        for (j, value) in self.basis.iter().zip(&self.rhs) {
+            if let Some(var) = vars.get_mut(*j) {
+                *var = *value;
+            }
+        }
+        let (big, small) = self.dot(self.rhs.iter())?;
+        if !big.is_zero() {
+            return Some(Err(SolutionError::Infinite));
+        }
+        Some(Ok(Solution {
+            vars: vars.iter().map(to_big_rational).collect(),
+            fn_val: to_big_rational(&small),
+        }))
+    }
+
+    /// `sum_k basis_coefficient_k * values_k`
+    fn dot<'a>(&self, values: impl IntoIterator<Item = &'a Rational128>) -> Option<BigNumber128> {
+        self.basis.iter().zip(values).try_fold(
+            (Rational128::zero(), Rational128::zero()),
+            |(big, small), (j, value)| {
+                let (coefficient_big, coefficient_small) = &self.coefficients[*j];
+                Some((
+                    big.checked_add(&coefficient_big.checked_mul(value)?)?,
+                    small.checked_add(&coefficient_small.checked_mul(value)?)?,
+                ))
+            },
+        )
+    }
+
+    /// Same rule, as of the [`SimplexTable`](super::SimplexTable): the greatest positive estimation,
+    /// ties are broken by the smallest column index
+    fn pivot_column(&self) -> Option<Option<usize>> {
+        let mut best: Option<(usize, BigNumber128)> = None;
+        for j in 0..self.tableau.ncols() {
+            let (big, small) = self.dot(self.tableau.column(j).iter())?;
+            let (coefficient_big, coefficient_small) = &self.coefficients[j];
+            let estimation = (
+                big.checked_sub(coefficient_big)?,
+                small.checked_sub(coefficient_small)?,
+            );
+            if estimation <= (Rational128::zero(), Rational128::zero()) {
+                continue;
+            }
+            if !matches!(&best, Some((_, best)) if best >= &estimation) {
+                best = Some((j, estimation));
+            }
+        }
+        Some(best.map(|(j, _)| j))
+    }
+
+    /// Minimum ratio test, the first of the tied rows wins, unless the test is lexicographic.
+    /// The inner [`None`], if the column has no positive elements, so the solution is infinite
+    fn pivot_row(&self, pivot_col: usize) -> Option<Option<usize>> {
+        let mut best: Option<usize> = None;
+        for i in (0..self.tableau.nrows()).filter(|i| self.tableau[(*i, pivot_col)] > Zero::zero())
+        {
+            let Some(best_i) = best else {
+                best = Some(i);
+                continue;
+            };
+            // rhs_i / el_i < rhs_best / el_best
+            let ordering = self.rhs[i]
+                .checked_mul(&self.tableau[(best_i, pivot_col)])?
+                .cmp(&self.rhs[best_i].checked_mul(&self.tableau[(i, pivot_col)])?);
+            let ordering = match ordering {
+                cmp::Ordering::Equal if self.lexicographic_ratio_test => {
+                    self.lexicographic_cmp(i, best_i, pivot_col)?
+                }
+                ordering => ordering,
+            };
+            if ordering.is_lt() {
+                best = Some(i);
+            }
+        }
+        Some(best)
+    }
+
+    fn lexicographic_cmp(&self, i1: usize, i2: usize, pivot_col: usize) -> Option<cmp::Ordering> {
+        let pivot_el1 = &self.tableau[(i1, pivot_col)];
+        let pivot_el2 = &self.tableau[(i2, pivot_col)];
+        for j in &self.initial_basis {
+            let ordering = self.tableau[(i1, *j)]
+                .checked_div(pivot_el1)?
+                .cmp(&self.tableau[(i2, *j)].checked_div(pivot_el2)?);
+            if ordering.is_ne() {
+                return Some(ordering);
+            }
+        }
+        Some(cmp::Ordering::Equal)
+    }
+
+    fn pivot(&mut self, pivot_row: usize, pivot_col: usize) -> Option<()> {
+        let pivot_el = self.tableau[(pivot_row, pivot_col)];
+        self.rhs[pivot_row] = self.rhs[pivot_row].checked_div(&pivot_el)?;
+        for el in self.tableau.row_mut(pivot_row).iter_mut() {
+            *el = el.checked_div(&pivot_el)?;
+        }
+
+        for i in (0..self.tableau.nrows()).filter(|i| i != &pivot_row) {
+            let multiplier = self.tableau[(i, pivot_col)];
+            self.rhs[i] =
+                self.rhs[i].checked_sub(&self.rhs[pivot_row].checked_mul(&multiplier)?)?;
+            for j in 0..self.tableau.ncols() {
+                let subtrahend = self.tableau[(pivot_row, j)].checked_mul(&multiplier)?;
+                self.tableau[(i, j)] = self.tableau[(i, j)].checked_sub(&subtrahend)?;
+            }
+        }
+
+        self.basis[pivot_row] = pivot_col;
+        Some(())
+    }
+}
+
+/// [`None`], if the `value` is infinite, or doesn't fit into `i128`
+fn to_rational128(value: &BigRationalExt) -> Option<Rational128> {
+    let value = value.finite_as_ref()?;
+    // Already reduced, with the positive denominator
+    Some(Rational128::new_raw(
+        value.numer().to_i128()?,
+        value.denom().to_i128()?,
+    ))
+}
+
+fn to_big_rational(value: &Rational128) -> BigRational {
+    BigRational::new_raw(BigInt::from(*value.numer()), BigInt::from(*value.denom()))
+}
+
+fn neg(value: &Rational128) -> Option<Rational128> {
+    Rational128::zero().checked_sub(value)
+}
+
+fn is_unit_column(column: DVectorSlice<Rational128>, k: usize) -> bool {
+    column
+        .iter()
+        .enumerate()
+        .all(|(i, el)| if i == k { el.is_one() } else { el.is_zero() })
+}
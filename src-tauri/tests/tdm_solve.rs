@@ -0,0 +1,55 @@
+use std::process::Command;
+
+fn tdm_solve(args: &[&str]) -> (Option<i32>, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_tdm-solve"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap();
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn solves_the_ships_problem() {
+    let (code, stdout, _) = tdm_solve(&["tests/data/ships.json"]);
+
+    assert_eq!(code, Some(0));
+    assert!(stdout.contains("[40, 0, 0, 0, 8, 0, 0, 12, 30, 10, 0, 0]"));
+    assert!(stdout.contains("Значення функції: 2564"));
+}
+
+#[test]
+fn solves_the_relaxation_of_the_ships_problem() {
+    let (code, stdout, _) = tdm_solve(&["--relax", "tests/data/ships.json"]);
+
+    assert_eq!(code, Some(0));
+    assert!(stdout.contains("[40, 0, 2/3, 0, 8, 0, 0, 12, 88/3, 10, 0, 0]"));
+    assert!(stdout.contains("Значення функції: 2564"));
+}
+
+#[test]
+fn fails_on_an_infeasible_problem() {
+    let (code, _, _) = tdm_solve(&["tests/data/infeasible.json"]);
+
+    assert_eq!(code, Some(1));
+}
+
+#[test]
+fn fails_on_an_unbounded_problem() {
+    let (code, _, stderr) = tdm_solve(&["tests/data/unbounded.json"]);
+
+    assert_eq!(code, Some(1));
+    assert!(stderr.contains("Розв'язок нескінченний"));
+}
+
+#[test]
+fn fails_on_the_iteration_limit() {
+    let (code, stdout, _) = tdm_solve(&["--max-iterations", "3", "tests/data/ships.json"]);
+
+    assert_eq!(code, Some(1));
+    assert!(stdout.contains("Iterations: 3"));
+}
@@ -1,13 +1,16 @@
+use std::{cell::RefCell, rc::Rc};
+
 use nalgebra::{DMatrix, DVector, RowDVector};
 use num_rational::BigRational;
-use wasm_bindgen::prelude::*;
-use wasm_bindgen_futures::spawn_local;
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
 
 use crate::{
     command,
     component::{Math, Solution, Table},
-    reclone,
+    config, reclone,
 };
 
 #[wasm_bindgen]
@@ -19,7 +22,7 @@ extern "C" {
     pub fn log_json(json: &JsValue);
 
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"])]
-    pub async fn listen(name: &str, callback: &Closure<dyn FnMut(String)>) -> JsValue;
+    pub async fn listen(name: &str, callback: &Closure<dyn FnMut(JsValue)>) -> JsValue;
 }
 
 #[function_component]
@@ -27,7 +30,8 @@ pub fn App() -> Html {
     let available_ship_line =
         use_state_eq::<DMatrix<bool>, _>(|| DMatrix::from_row_slice(4, 3, &[true; 12]));
 
-    let transport_rate = use_state_eq::<DMatrix<f64>, _>(|| {
+    // Numbers are kept as they were typed, so that decimals stay exact in the solver
+    let transport_rate = use_state_eq::<DMatrix<String>, _>(|| {
         DMatrix::from_row_slice(
             4,
             3,
@@ -38,6 +42,7 @@ pub fn App() -> Html {
                 50., 17., 45., //
             ],
         )
+        .map(|a_ij: f64| a_ij.to_string())
         // DMatrix::from_row_slice(
         //     3,
         //     4,
@@ -48,7 +53,7 @@ pub fn App() -> Html {
         //     ],
         // )
     });
-    let cost_rate = use_state_eq::<DMatrix<f64>, _>(|| {
+    let cost_rate = use_state_eq::<DMatrix<String>, _>(|| {
         DMatrix::from_row_slice(
             4,
             3,
@@ -59,6 +64,7 @@ pub fn App() -> Html {
                 40., 45., 65., //
             ],
         )
+        .map(|c_ij: f64| c_ij.to_string())
         // DMatrix::from_row_slice(
         //     3,
         //     4,
@@ -69,24 +75,59 @@ pub fn App() -> Html {
         //     ],
         // )
     });
-    let min_transport_per_line = use_state_eq::<DVector<f64>, _>(
-        || DVector::from_column_slice(&[300., 200., 1000., 500.]), // DVector::from_column_slice(&[600., 2000., 1200.]),
+    let min_transport_per_line = use_state_eq::<DVector<String>, _>(
+        || DVector::from_column_slice(&[300., 200., 1000., 500.]).map(|a_i: f64| a_i.to_string()), // DVector::from_column_slice(&[600., 2000., 1200.]),
     );
+    let minimize = use_state_eq(|| true);
+    let fleet_usage = use_state_eq(command::FleetUsage::default);
     let ships_count_per_type = use_state_eq::<RowDVector<u16>, _>(
         || RowDVector::from_row_slice(&[50, 20, 30]), // RowDVector::from_row_slice(&[40, 60, 20, 70]),
     );
 
-    assert_eq!(transport_rate.shape(), cost_rate.shape());
-    assert_eq!(ships_count_per_type.ncols(), transport_rate.ncols());
-    assert_eq!(min_transport_per_line.nrows(), transport_rate.nrows());
+    let shape_errors = shape_errors(
+        transport_rate.shape(),
+        cost_rate.shape(),
+        min_transport_per_line.nrows(),
+        ships_count_per_type.ncols(),
+    );
 
     let i_max = min_transport_per_line.nrows();
     let j_max = ships_count_per_type.ncols();
 
     let is_loading = use_state_eq(|| false);
+    let progress = use_state_eq(|| None::<command::Progress>);
+
+    {
+        reclone!(progress);
+        use_effect_with_deps(
+            move |_| {
+                let callback = Closure::<dyn FnMut(JsValue)>::new(move |event| {
+                    if let Some(new_progress) = command::Progress::from_event(event) {
+                        progress.set(Some(new_progress));
+                    }
+                });
+                // `listen` resolves to the function, that removes the listener.
+                // The callback has to live until then
+                let listener = Rc::new(RefCell::new(None::<(js_sys::Function, _)>));
+                spawn_local({
+                    reclone!(listener);
+                    async move {
+                        let unlisten = listen(command::PROGRESS_EVENT, &callback).await;
+                        *listener.borrow_mut() = Some((unlisten.unchecked_into(), callback));
+                    }
+                });
+                move || {
+                    if let Some((unlisten, _callback)) = listener.borrow_mut().take() {
+                        let _ = unlisten.call0(&JsValue::NULL);
+                    }
+                }
+            },
+            (),
+        );
+    }
 
     let response =
-        use_state::<Result<(DMatrix<BigRational>, BigRational), Option<AttrValue>>, _>(|| {
+        use_state::<Result<command::SolutionOutcome<BigRational>, Option<AttrValue>>, _>(|| {
             Err(None)
         });
 
@@ -97,8 +138,11 @@ pub fn App() -> Html {
             cost_rate,
             min_transport_per_line,
             ships_count_per_type,
+            minimize,
+            fleet_usage,
             response,
             is_loading,
+            progress,
         );
         Callback::from(move |_| {
             reclone!(
@@ -107,10 +151,14 @@ pub fn App() -> Html {
                 cost_rate,
                 min_transport_per_line,
                 ships_count_per_type,
+                minimize,
+                fleet_usage,
                 response,
-                is_loading
+                is_loading,
+                progress
             );
             spawn_local(async move {
+                progress.set(None);
                 is_loading.set(true);
                 let solution = command::compute(
                     &available_ship_line,
@@ -118,6 +166,8 @@ pub fn App() -> Html {
                     &cost_rate,
                     &min_transport_per_line,
                     &ships_count_per_type,
+                    *minimize,
+                    *fleet_usage,
                 )
                 .await;
                 is_loading.set(false);
@@ -129,26 +179,145 @@ pub fn App() -> Html {
         })
     };
 
+    let cancel = Callback::from(|_| spawn_local(command::cancel_compute()));
+
+    let import_error = use_state_eq(|| None::<String>);
+    let onchange_import = {
+        reclone!(
+            available_ship_line,
+            transport_rate,
+            cost_rate,
+            min_transport_per_line,
+            ships_count_per_type,
+            minimize,
+            fleet_usage,
+            import_error,
+        );
+        Callback::from(move |e: Event| {
+            let Some(file) = e
+                .target()
+                .unwrap()
+                .unchecked_into::<HtmlInputElement>()
+                .files()
+                .and_then(|files| files.get(0))
+            else {
+                return;
+            };
+            reclone!(
+                available_ship_line,
+                transport_rate,
+                cost_rate,
+                min_transport_per_line,
+                ships_count_per_type,
+                minimize,
+                fleet_usage,
+                import_error
+            );
+            spawn_local(async move {
+                let configuration = JsFuture::from(file.text())
+                    .await
+                    .ok()
+                    .and_then(|text| text.as_string())
+                    .ok_or_else(|| "Не вдалося прочитати файл".to_owned())
+                    .and_then(|text| config::parse_configuration(&text));
+                // The inputs are only replaced all together, once the file is valid
+                match configuration {
+                    Ok(configuration) => {
+                        available_ship_line.set(configuration.available_ship_line);
+                        transport_rate.set(configuration.transport_rate);
+                        cost_rate.set(configuration.cost_rate);
+                        min_transport_per_line.set(configuration.min_transport_per_line);
+                        ships_count_per_type.set(configuration.ships_count_per_type);
+                        minimize.set(configuration.minimize);
+                        fleet_usage.set(configuration.fleet_usage);
+                        import_error.set(None);
+                    }
+                    Err(err_msg) => import_error.set(Some(err_msg)),
+                }
+            })
+        })
+    };
+
+    let onchange_set_minimize = {
+        reclone!(minimize);
+        Callback::from(move |e: Event| {
+            minimize.set(
+                !e.target()
+                    .unwrap()
+                    .unchecked_into::<HtmlInputElement>()
+                    .checked(),
+            )
+        })
+    };
+    let sense = if *minimize { r"\min" } else { r"\max" };
+
+    let onchange_set_fleet_usage = {
+        reclone!(fleet_usage);
+        Callback::from(move |e: Event| {
+            fleet_usage.set(
+                if e.target()
+                    .unwrap()
+                    .unchecked_into::<HtmlInputElement>()
+                    .checked()
+                {
+                    command::FleetUsage::AtMost
+                } else {
+                    command::FleetUsage::Exact
+                },
+            )
+        })
+    };
+    let fleet_sign = match *fleet_usage {
+        command::FleetUsage::Exact => "=",
+        command::FleetUsage::AtMost => r"\le",
+    };
+
     html! {
         <main class={classes!("container")}>
-            <Table
-                {available_ship_line}
-                {transport_rate}
-                {cost_rate}
-                {min_transport_per_line}
-                {ships_count_per_type}
-            />
+            <label>
+                {"Імпортувати з файлу "}
+                <input type="file" accept=".json" onchange={onchange_import} />
+            </label>
+            if let Some(err_msg) = &*import_error {
+                <p class={classes!("error")}>{err_msg}</p>
+            }
+            if shape_errors.is_empty() {
+                <Table
+                    {available_ship_line}
+                    {transport_rate}
+                    {cost_rate}
+                    {min_transport_per_line}
+                    {ships_count_per_type}
+                />
+            } else {
+                {for shape_errors.into_iter().map(|err_msg| html! {
+                    <p class={classes!("error")}>{err_msg}</p>
+                })}
+            }
             <div style="padding-top: 2em;"/>
+            <label>
+                <input type="checkbox" checked={!*minimize} onchange={onchange_set_minimize} />
+                {"Максимізація"}
+            </label>
+            <label>
+                <input
+                    type="checkbox"
+                    checked={*fleet_usage == command::FleetUsage::AtMost}
+                    onchange={onchange_set_fleet_usage}
+                />
+                {"Дозволити невикористані судна"}
+            </label>
             <Math
                 expression={format!(
-                    r"\sum\limits_{{i=1}}^{i_max}\sum\limits_{{j=1}}^{j_max} c_{{ij}}n_{{ij}}\rightarrow\min"
+                    r"\sum\limits_{{i=1}}^{i_max}\sum\limits_{{j=1}}^{j_max} c_{{ij}}n_{{ij}}\rightarrow{sense}"
                 )}
                 centered=true
+                display=true
             />
             <p>{"За обмежень:"}</p>
             <Math
                 expression={format!(
-                    r"\sum\limits_{{i=1}}^{i_max}n_{{ij}}=N_j,\quad j=\overline{{1,{j_max}}}"
+                    r"\sum\limits_{{i=1}}^{i_max}n_{{ij}}{fleet_sign}N_j,\quad j=\overline{{1,{j_max}}}"
                 )}
             />
             <Math
@@ -164,8 +333,58 @@ pub fn App() -> Html {
             <button onclick={solve}>
                 {"Розв'язати"}
             </button>
+            if *is_loading {
+                <button onclick={cancel}>
+                    {"Скасувати"}
+                </button>
+            }
 
-            <Solution {is_loading} solution_or_err={response} />
+            <Solution {is_loading} {progress} solution_or_err={response} />
         </main>
     }
 }
+
+/// Describes the inputs, that don't fit the size of the others, instead of panicking
+pub(crate) fn shape_errors(
+    transport_rate_shape: (usize, usize),
+    cost_rate_shape: (usize, usize),
+    n_lines: usize,
+    n_ships: usize,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    if transport_rate_shape != cost_rate_shape {
+        errors.push(format!(
+            "Розміри матриць обсягів {transport_rate_shape:?} та вартостей {cost_rate_shape:?} перевезень не збігаються"
+        ));
+    }
+    if n_ships != transport_rate_shape.1 {
+        errors.push(format!(
+            "Кількість типів суден {n_ships} не збігається з кількістю стовпців {}",
+            transport_rate_shape.1
+        ));
+    }
+    if n_lines != transport_rate_shape.0 {
+        errors.push(format!(
+            "Кількість ліній {n_lines} не збігається з кількістю рядків {}",
+            transport_rate_shape.0
+        ));
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mismatched_shapes_are_reported() {
+        assert!(shape_errors((4, 3), (4, 3), 4, 3).is_empty());
+        assert_eq!(
+            shape_errors((4, 3), (4, 2), 3, 3),
+            [
+                "Розміри матриць обсягів (4, 3) та вартостей (4, 2) перевезень не збігаються",
+                "Кількість ліній 3 не збігається з кількістю рядків 4",
+            ]
+        );
+    }
+}
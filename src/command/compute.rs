@@ -1,13 +1,13 @@
-use nalgebra::{DMatrix, DVector, RowDVector};
+use nalgebra::{DMatrix, DVector, RowDVector, Scalar};
 use num_rational::BigRational;
 use num_traits::Zero;
 use ratio_extension::BigRationalExt;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_wasm_bindgen::{from_value, to_value};
 
 use crate::app::{log, log_json};
 
-use super::invoke_args;
+use super::{invoke, invoke_args};
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -16,30 +16,68 @@ struct ComputeArgs<'a> {
     cost_rate: &'a DMatrix<BigRationalExt>,
     min_transport_per_line: &'a DVector<BigRationalExt>,
     ships_count_per_type: &'a RowDVector<u16>,
+    minimize: bool,
+    fleet_usage: FleetUsage,
+}
+
+/// How the fleet of every ship type is used: `sum_i n_ij = N_j` or `sum_i n_ij <= N_j`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FleetUsage {
+    /// Every ship is used
+    #[default]
+    Exact,
+    /// Some ships may stay unused
+    AtMost,
+}
+
+/// Result of the finished [`compute`]. The infeasible and the unbounded problems are not errors
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(
+    tag = "status",
+    rename_all = "camelCase",
+    bound(deserialize = "T: Scalar + Deserialize<'de>")
+)]
+pub enum SolutionOutcome<T: Scalar> {
+    Finite {
+        /// Ships of every type on every line
+        matrix: DMatrix<T>,
+        /// Objective in the sense of the problem
+        value: T,
+        minimize: bool,
+    },
+    Infeasible,
+    Unbounded,
 }
 
 pub async fn compute<'a>(
     available_ship_line: &'a DMatrix<bool>,
-    transport_rate: &'a DMatrix<f64>,
-    cost_rate: &'a DMatrix<f64>,
-    min_transport_per_line: &'a DVector<f64>,
+    transport_rate: &'a DMatrix<String>,
+    cost_rate: &'a DMatrix<String>,
+    min_transport_per_line: &'a DVector<String>,
     ships_count_per_type: &'a RowDVector<u16>,
-) -> Result<(DMatrix<BigRational>, BigRational), String> {
-    let transport_rate = transport_rate.zip_map(available_ship_line, |a_ij, available| {
-        if !available {
-            BigRationalExt::zero()
-        } else {
-            BigRationalExt::from_float(a_ij)
-        }
-    });
-    let cost_rate = cost_rate.zip_map(available_ship_line, |c_ij, available| {
-        if !available {
+    minimize: bool,
+    fleet_usage: FleetUsage,
+) -> Result<SolutionOutcome<BigRational>, String> {
+    check_unavailable_ship_types(available_ship_line, ships_count_per_type, fleet_usage)?;
+
+    let transport_rate = parse_rates(transport_rate, available_ship_line, BigRationalExt::zero())?;
+    // An unavailable ship must never be the best choice
+    let cost_rate = parse_rates(
+        cost_rate,
+        available_ship_line,
+        if minimize {
             BigRationalExt::Inf
         } else {
-            BigRationalExt::from_float(c_ij)
-        }
-    });
-    let min_transport_per_line = min_transport_per_line.map(BigRationalExt::from_float);
+            BigRationalExt::MinusInf
+        },
+    )?;
+    let min_transport_per_line = DVector::from_vec(
+        min_transport_per_line
+            .iter()
+            .map(|a_i| parse_decimal(a_i))
+            .collect::<Result<Vec<_>, _>>()?,
+    );
     log_json(&to_value(&transport_rate).unwrap());
     log_json(&to_value(&cost_rate).unwrap());
     let args = ComputeArgs {
@@ -47,6 +85,8 @@ pub async fn compute<'a>(
         cost_rate: &cost_rate,
         min_transport_per_line: &min_transport_per_line,
         ships_count_per_type,
+        minimize,
+        fleet_usage,
     };
     let response = invoke_args("compute", to_value(&args).unwrap()).await;
 
@@ -63,3 +103,184 @@ pub async fn compute<'a>(
         }
     }
 }
+
+/// Asks the running [`compute`] to stop. It then returns the cancellation message
+pub async fn cancel_compute() {
+    if let Err(err_msg) = invoke("cancel_compute").await {
+        log("Error occurred");
+        log_json(&err_msg);
+    }
+}
+
+/// Parses the rates of available ships, and replaces the rest with `unavailable`
+fn parse_rates(
+    rates: &DMatrix<String>,
+    available_ship_line: &DMatrix<bool>,
+    unavailable: BigRationalExt,
+) -> Result<DMatrix<BigRationalExt>, String> {
+    let rates = rates
+        .iter()
+        .zip(available_ship_line.iter())
+        .map(|(rate, available)| {
+            if !available {
+                Ok(unavailable.clone())
+            } else {
+                parse_decimal(rate)
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(DMatrix::from_vec(
+        available_ship_line.nrows(),
+        available_ship_line.ncols(),
+        rates,
+    ))
+}
+
+fn parse_decimal(value: &str) -> Result<BigRationalExt, String> {
+    BigRationalExt::from_decimal_str(value).map_err(|_| format!("Некоректне число: \"{value}\""))
+}
+
+/// Rejects a ship type, that is unavailable on every line, but still has ships to assign.
+/// Its count constraint can't be satisfied, so the solver would just report an absent solution.
+/// With [`FleetUsage::AtMost`] its ships just stay unused, so nothing is rejected
+fn check_unavailable_ship_types(
+    available_ship_line: &DMatrix<bool>,
+    ships_count_per_type: &RowDVector<u16>,
+    fleet_usage: FleetUsage,
+) -> Result<(), String> {
+    if fleet_usage == FleetUsage::AtMost {
+        return Ok(());
+    }
+    match available_ship_line
+        .column_iter()
+        .zip(ships_count_per_type.iter())
+        .position(|(available, count)| *count > 0 && !available.iter().any(|a| *a))
+    {
+        Some(j) => Err(format!(
+            "Судна типу {} недоступні на жодній лінії, але їх кількість {}",
+            j + 1,
+            ships_count_per_type[j]
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unavailable_ship_type_with_ships_is_rejected() {
+        let mut available_ship_line = DMatrix::from_element(4, 3, true);
+        let ships_count_per_type = RowDVector::from_row_slice(&[50, 20, 30]);
+        assert_eq!(
+            check_unavailable_ship_types(
+                &available_ship_line,
+                &ships_count_per_type,
+                FleetUsage::Exact
+            ),
+            Ok(())
+        );
+
+        available_ship_line.column_mut(1).fill(false);
+        assert_eq!(
+            check_unavailable_ship_types(
+                &available_ship_line,
+                &ships_count_per_type,
+                FleetUsage::Exact
+            ),
+            Err("Судна типу 2 недоступні на жодній лінії, але їх кількість 20".to_owned())
+        );
+        // The unused ships are allowed
+        assert_eq!(
+            check_unavailable_ship_types(
+                &available_ship_line,
+                &ships_count_per_type,
+                FleetUsage::AtMost
+            ),
+            Ok(())
+        );
+
+        let ships_count_per_type = RowDVector::from_row_slice(&[50, 0, 30]);
+        assert_eq!(
+            check_unavailable_ship_types(
+                &available_ship_line,
+                &ships_count_per_type,
+                FleetUsage::Exact
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn compute_args_carry_the_sense_and_the_fleet_usage() {
+        let zeros = DMatrix::zeros(1, 1);
+        let args = ComputeArgs {
+            transport_rate: &zeros,
+            cost_rate: &zeros,
+            min_transport_per_line: &DVector::zeros(1),
+            ships_count_per_type: &RowDVector::zeros(1),
+            minimize: false,
+            fleet_usage: FleetUsage::AtMost,
+        };
+
+        let json = serde_json::to_value(&args).unwrap();
+        assert_eq!(json["minimize"], serde_json::Value::Bool(false));
+        assert_eq!(json["fleetUsage"], serde_json::Value::from("atMost"));
+        assert!(json.get("shipsCountPerType").is_some());
+    }
+
+    #[test]
+    fn solution_outcome_is_told_by_the_status() {
+        assert_eq!(
+            serde_json::from_value::<SolutionOutcome<BigRational>>(
+                serde_json::json!({ "status": "unbounded" })
+            )
+            .unwrap(),
+            SolutionOutcome::Unbounded
+        );
+
+        let matrix =
+            DMatrix::from_row_slice(1, 2, &[BigRational::from_integer(1.into()), Zero::zero()]);
+        let value = BigRational::new(3.into(), 2.into());
+        assert_eq!(
+            serde_json::from_value::<SolutionOutcome<BigRational>>(serde_json::json!({
+                "status": "finite",
+                "matrix": matrix,
+                "value": value,
+                "minimize": true,
+            }))
+            .unwrap(),
+            SolutionOutcome::Finite {
+                matrix,
+                value,
+                minimize: true,
+            }
+        );
+    }
+
+    #[test]
+    fn decimal_rates_are_parsed_exactly() {
+        let rates = DMatrix::from_row_slice(2, 2, &["0.1", "2.5", "3", "40"]).map(str::to_owned);
+        let mut available_ship_line = DMatrix::from_element(2, 2, true);
+        available_ship_line[(1, 0)] = false;
+
+        assert_eq!(
+            parse_rates(&rates, &available_ship_line, BigRationalExt::Inf),
+            Ok(DMatrix::from_row_slice(
+                2,
+                2,
+                &[
+                    BigRationalExt::Finite(BigRational::new(1.into(), 10.into())),
+                    BigRationalExt::Finite(BigRational::new(5.into(), 2.into())),
+                    BigRationalExt::Inf,
+                    BigRationalExt::from_integer(40.into()),
+                ]
+            ))
+        );
+        assert_eq!(
+            parse_decimal("1.2.3"),
+            Err("Некоректне число: \"1.2.3\"".to_owned())
+        );
+    }
+}
@@ -1,6 +1,8 @@
 mod compute;
+mod progress;
 
 pub use compute::*;
+pub use progress::*;
 
 use wasm_bindgen::prelude::*;
 
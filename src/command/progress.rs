@@ -0,0 +1,70 @@
+use std::fmt;
+
+use num_rational::BigRational;
+use serde::Deserialize;
+use serde_wasm_bindgen::from_value;
+use wasm_bindgen::JsValue;
+
+/// Name of the event, that the backend emits while solving
+pub const PROGRESS_EVENT: &str = "compute-progress";
+
+/// Branch-and-bound progress of the running solve
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Progress {
+    pub nodes_explored: u32,
+    pub best_objective: Option<BigRational>,
+}
+
+/// Tauri event, only the payload of which is needed
+#[derive(Debug, Deserialize)]
+struct Event<T> {
+    payload: T,
+}
+
+impl Progress {
+    pub fn from_event(event: JsValue) -> Option<Self> {
+        from_value::<Event<Self>>(event)
+            .map(|event| event.payload)
+            .ok()
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Досліджено вузлів: {}", self.nodes_explored)?;
+        match &self.best_objective {
+            Some(best_objective) => write!(f, ", найкраще значення: {best_objective}"),
+            None => write!(f, ", цілочисельний розв'язок ще не знайдено"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_event_updates_the_counter() {
+        let event: Event<Progress> = serde_json::from_value(serde_json::json!({
+            "event": PROGRESS_EVENT,
+            "windowLabel": "main",
+            "id": 0,
+            "payload": { "nodesExplored": 3, "bestObjective": null },
+        }))
+        .unwrap();
+        assert_eq!(
+            event.payload.to_string(),
+            "Досліджено вузлів: 3, цілочисельний розв'язок ще не знайдено"
+        );
+
+        let progress = Progress {
+            nodes_explored: 7,
+            best_objective: Some(BigRational::from_integer(2564.into())),
+        };
+        assert_eq!(
+            progress.to_string(),
+            "Досліджено вузлів: 7, найкраще значення: 2564"
+        );
+    }
+}
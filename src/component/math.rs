@@ -22,6 +22,18 @@ pub struct Props {
     pub expression: AttrValue,
     #[prop_or_default]
     pub centered: bool,
+    /// Typesets the expression as the display math, with the operators of the full size
+    #[prop_or_default]
+    pub display: bool,
+}
+
+/// Opening and closing delimiters of the inline or the display math
+fn delimiters(display: bool) -> (&'static str, &'static str) {
+    if display {
+        (r"\[", r"\]")
+    } else {
+        (r"\(", r"\)")
+    }
 }
 
 #[function_component]
@@ -29,12 +41,13 @@ pub fn Math(
     Props {
         expression,
         centered,
+        display,
     }: &Props,
 ) -> Html {
     let node_ref = use_node_ref();
     {
         let node_ref = node_ref.clone();
-        let expression = expression.clone();
+        let deps = (expression.clone(), *display);
         use_effect_with_deps(
             move |_| {
                 log("Performing use_effect_with_deps");
@@ -44,9 +57,10 @@ pub fn Math(
                     .into_js_result()
                     .unwrap()]))
             },
-            expression,
+            deps,
         );
     }
+    let (open, close) = delimiters(*display);
     let mut class = Classes::new();
     if *centered {
         class.push("centered");
@@ -55,18 +69,29 @@ pub fn Math(
         Some(node) => {
             log("Non-empty node_ref");
             math_jax_typeset_clear(Box::new([(&node).into_js_result().unwrap()]));
-            node.set_text_content(Some(&format!(r"\({expression}\)")));
+            node.set_text_content(Some(&format!("{open}{expression}{close}")));
 
             html! {<div ref={node_ref} {class}>
-                {r"\("}{expression}{r"\)"}
+                {open}{expression}{close}
             </div>}
         }
         None => {
             log("Empty node_ref");
 
             html! {<div ref={node_ref} {class}>
-                {r"\("}{expression}{r"\)"}
+                {open}{expression}{close}
             </div>}
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_math_has_its_own_delimiters() {
+        assert_eq!(delimiters(false), (r"\(", r"\)"));
+        assert_eq!(delimiters(true), (r"\[", r"\]"));
+    }
+}
@@ -1,89 +1,191 @@
 use std::{cmp::PartialEq, fmt::Display};
 
 use lazy_static::lazy_static;
-use nalgebra::{DMatrix, Scalar};
+use nalgebra::Scalar;
 use num_bigint::BigInt;
 use num_rational::BigRational;
-use num_traits::{Signed, Zero};
+use num_traits::{Signed, ToPrimitive, Zero};
 use yew::{function_component, html, AttrValue, Html, Properties, UseStateHandle};
 
-use crate::component::Math;
+use crate::{
+    command::{Progress, SolutionOutcome},
+    component::Math,
+    helpers::f64_rounded_string,
+};
 
-pub type SolutionOrError<T> = UseStateHandle<Result<(DMatrix<T>, T), Option<AttrValue>>>;
+/// Outcome of the solve, or the message of the error
+pub type SolutionOrError<T> = UseStateHandle<Result<SolutionOutcome<T>, Option<AttrValue>>>;
 
 #[derive(Properties, PartialEq)]
 pub struct Props<T: Scalar + Display> {
     pub is_loading: UseStateHandle<bool>,
+    pub progress: UseStateHandle<Option<Progress>>,
     pub solution_or_err: SolutionOrError<T>,
+    /// Shows every fraction exactly, even with a huge denominator
+    #[prop_or_default]
+    pub exact: bool,
 }
 
 lazy_static! {
     static ref ZERO: BigInt = Zero::zero();
+    /// Fractions with greater denominators are shown as the rounded decimals, unless `exact`
+    static ref MAX_DISPLAY_DENOMINATOR: BigInt = BigInt::from(1_000_000);
 }
 
+/// Digits after the point of the rounded decimals
+const DISPLAY_PRECISION: usize = 6;
+
 #[function_component]
 pub fn Solution(
     Props {
         is_loading,
+        progress,
         solution_or_err,
+        exact,
     }: &Props<BigRational>,
 ) -> Html {
-    let ratio_to_latex = |ratio: &BigRational| -> String {
-        (if ratio.is_integer() {
-            ratio.to_integer().to_string()
-        } else if !ratio.trunc().is_zero() {
-            let whole = ratio.trunc().to_integer();
-            let frac = ratio.fract();
-            format!(
-                r"{whole}\frac{{{numer}}}{{{denom}}}",
-                numer = frac.numer(),
-                denom = frac.denom()
-            )
-        } else {
-            format!(
-                r"{sign}\frac{{{numer}}}{{{denom}}}",
-                sign = if ratio.numer() < &ZERO { "-" } else { "" },
-                numer = ratio.numer().abs(),
-                denom = ratio.denom()
-            )
-        })
-        .into()
-    };
-
     if **is_loading {
-        return html! { <p>{"Йде обчислення..."}</p> };
+        return match &**progress {
+            Some(progress) => html! { <p>{progress.to_string()}</p> },
+            None => html! { <p>{"Йде обчислення..."}</p> },
+        };
     }
     match &**solution_or_err {
-        Ok((matrix, function_value)) => {
-            html! {<>
+        Ok(outcome) => match outcome_latex(outcome, !*exact) {
+            Ok((matrix, function_value)) => html! {<>
                 <Math
                     centered=true
-                    expression={format!(
-                        r"\begin{{pmatrix}}{}\end{{pmatrix}}",
-                        matrix
-                            .row_iter()
-                            .map(|row| row
-                                .column_iter()
-                                .map(|el| {
-                                    let ratio = &el.x;
-                                    ratio_to_latex(ratio)
-                                })
-                                .collect::<Vec<_>>()
-                                .join("&")
-                            )
-                            .collect::<Vec<_>>()
-                            .join(r"\\")
-                    )}
+                    display=true
+                    expression={matrix}
                 />
                 <div style="padding-top: 1em;" />
                 <Math
-                    expression={format!("F={}", ratio_to_latex(function_value))}
+                    expression={function_value}
                     centered=true
                 />
-            </>}
-        }
+            </>},
+            Err(message) => html! { <p>{message}</p> },
+        },
         Err(err_msg) => html! { if let Some(err_msg) = err_msg {
             <p>{err_msg}</p>
         }},
     }
 }
+
+/// LaTeX of the solution matrix and of the objective function value,
+/// or the message, why there is no solution to show
+fn outcome_latex(
+    outcome: &SolutionOutcome<BigRational>,
+    simplify: bool,
+) -> Result<(String, String), &'static str> {
+    match outcome {
+        SolutionOutcome::Finite {
+            matrix,
+            value,
+            minimize,
+        } => Ok((
+            format!(
+                r"\begin{{pmatrix}}{}\end{{pmatrix}}",
+                matrix
+                    .row_iter()
+                    .map(|row| row
+                        .column_iter()
+                        .map(|el| ratio_to_latex(&el.x, simplify))
+                        .collect::<Vec<_>>()
+                        .join("&"))
+                    .collect::<Vec<_>>()
+                    .join(r"\\")
+            ),
+            format!(
+                r"F_{{{}}}={}",
+                if *minimize { r"\min" } else { r"\max" },
+                ratio_to_latex(value, simplify)
+            ),
+        )),
+        SolutionOutcome::Infeasible => {
+            Err("Задача не має допустимих розв'язків: обмеження несумісні")
+        }
+        SolutionOutcome::Unbounded => Err("Цільова функція задачі необмежена"),
+    }
+}
+
+/// With `simplify`, a fraction with a denominator above [`MAX_DISPLAY_DENOMINATOR`]
+/// is shown as the approximate decimal. The value itself stays exact
+fn ratio_to_latex(ratio: &BigRational, simplify: bool) -> String {
+    if simplify && ratio.denom() > &*MAX_DISPLAY_DENOMINATOR {
+        if let Some(float) = ratio.to_f64() {
+            return format!(r"\approx{}", f64_rounded_string(&float, DISPLAY_PRECISION));
+        }
+    }
+    if ratio.is_integer() {
+        ratio.to_integer().to_string()
+    } else if !ratio.trunc().is_zero() {
+        let whole = ratio.trunc().to_integer();
+        let frac = ratio.fract();
+        format!(
+            r"{whole}\frac{{{numer}}}{{{denom}}}",
+            numer = frac.numer(),
+            denom = frac.denom()
+        )
+    } else {
+        format!(
+            r"{sign}\frac{{{numer}}}{{{denom}}}",
+            sign = if ratio.numer() < &ZERO { "-" } else { "" },
+            numer = ratio.numer().abs(),
+            denom = ratio.denom()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::DMatrix;
+
+    use super::*;
+
+    #[test]
+    fn every_outcome_is_rendered() {
+        let int = |n: i32| BigRational::from_integer(n.into());
+        assert_eq!(
+            outcome_latex(
+                &SolutionOutcome::Finite {
+                    matrix: DMatrix::from_row_slice(2, 2, &[int(1), int(0), int(0), int(2)]),
+                    value: BigRational::new(7.into(), 2.into()),
+                    minimize: false,
+                },
+                true
+            ),
+            Ok((
+                r"\begin{pmatrix}1&0\\0&2\end{pmatrix}".to_owned(),
+                r"F_{\max}=3\frac{1}{2}".to_owned()
+            ))
+        );
+        assert_eq!(
+            outcome_latex(&SolutionOutcome::Infeasible, true),
+            Err("Задача не має допустимих розв'язків: обмеження несумісні")
+        );
+        assert_eq!(
+            outcome_latex(&SolutionOutcome::Unbounded, true),
+            Err("Цільова функція задачі необмежена")
+        );
+    }
+
+    #[test]
+    fn huge_denominators_are_shown_approximately() {
+        let huge = BigRational::new(7.into(), 9007199254740992u64.into());
+        assert_eq!(ratio_to_latex(&huge, true), r"\approx0");
+        let huge = BigRational::new(9007199254740993u64.into(), 9007199254740992u64.into());
+        assert_eq!(ratio_to_latex(&huge, true), r"\approx1");
+        let third = BigRational::new(1.into(), 3.into());
+        assert_eq!(ratio_to_latex(&third, true), r"\frac{1}{3}");
+        assert_eq!(
+            ratio_to_latex(&BigRational::new(7.into(), 3000000.into()), true),
+            r"\approx0.000002"
+        );
+        // The exact display keeps the fraction
+        assert_eq!(
+            ratio_to_latex(&BigRational::new(7.into(), 2000000.into()), false),
+            r"\frac{7}{2000000}"
+        );
+    }
+}
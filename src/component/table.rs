@@ -1,19 +1,25 @@
-use std::ops::Deref;
+use std::{cell::RefCell, ops::Deref, ops::IndexMut, rc::Rc, time::Duration};
 
 use nalgebra::{DMatrix, DVector, RowDVector};
+use num_rational::BigRational;
+use num_traits::Signed;
+use ratio_extension::{BigRationalExt, RatioExt};
 use wasm_bindgen::JsCast;
-use web_sys::{HtmlInputElement, SubmitEvent};
-use yew::html::onchange;
-use yew::{classes, function_component, html, Callback, Html, Properties, UseStateHandle};
+use web_sys::{ClipboardEvent, HtmlInputElement, SubmitEvent};
+use yew::html::{onchange, onpaste};
+use yew::platform::{spawn_local, time::sleep};
+use yew::{
+    classes, function_component, html, use_mut_ref, Callback, Html, Properties, UseStateHandle,
+};
 
 use crate::reclone;
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
     pub available_ship_line: UseStateHandle<DMatrix<bool>>,
-    pub transport_rate: UseStateHandle<DMatrix<f64>>,
-    pub cost_rate: UseStateHandle<DMatrix<f64>>,
-    pub min_transport_per_line: UseStateHandle<DVector<f64>>,
+    pub transport_rate: UseStateHandle<DMatrix<String>>,
+    pub cost_rate: UseStateHandle<DMatrix<String>>,
+    pub min_transport_per_line: UseStateHandle<DVector<String>>,
     pub ships_count_per_type: UseStateHandle<RowDVector<u16>>,
 }
 
@@ -34,58 +40,68 @@ pub fn Table(
 
     let onsubmit = Callback::from(|e: SubmitEvent| e.prevent_default());
 
+    // Edits of the values are set on blur or enter, as before, so the state is current, when the problem is solved.
+    // Only the pasted blocks are coalesced for DEBOUNCE, and the next edit sets them at once
+    let (set_transport_rate, paste_transport_rate) =
+        edit_setters(transport_rate.clone(), use_mut_ref(Default::default));
+    let (set_cost_rate, paste_cost_rate) =
+        edit_setters(cost_rate.clone(), use_mut_ref(Default::default));
+    let (set_min_transport_per_line, _) = edit_setters(
+        min_transport_per_line.clone(),
+        use_mut_ref(Default::default),
+    );
+    let (set_ships_count_per_type, _) =
+        edit_setters(ships_count_per_type.clone(), use_mut_ref(Default::default));
+
     let onchange_set_transport_rate = {
-        reclone!(transport_rate);
+        reclone!(set_transport_rate);
         Callback::from(move |(e, i, j): (onchange::Event, usize, usize)| {
-            let mut new_transport_rate = transport_rate.deref().to_owned();
-            new_transport_rate[(i, j)] = e
-                .target()
-                .unwrap()
-                .unchecked_into::<HtmlInputElement>()
-                .value_as_number();
-            UseStateHandle::set(&transport_rate, new_transport_rate)
+            set_transport_rate.emit(vec![((i, j), input_value(&e))])
         })
     };
 
     let onchange_set_cost_rate = {
-        reclone!(cost_rate);
+        reclone!(set_cost_rate);
         Callback::from(move |(e, i, j): (onchange::Event, usize, usize)| {
-            let mut new_cost_rate = cost_rate.deref().to_owned();
-            new_cost_rate[(i, j)] = e
-                .target()
-                .unwrap()
-                .unchecked_into::<HtmlInputElement>()
-                .value_as_number();
-            UseStateHandle::set(&cost_rate, new_cost_rate)
+            set_cost_rate.emit(vec![((i, j), input_value(&e))])
         })
     };
 
-    let onchange_set_min_transport_per_line = {
-        reclone!(min_transport_per_line);
-        Callback::from(move |(e, i): (onchange::Event, usize)| {
-            let mut new_min_transport_per_line = min_transport_per_line.deref().to_owned();
-            new_min_transport_per_line[i] = e
-                .target()
-                .unwrap()
-                .unchecked_into::<HtmlInputElement>()
-                .value_as_number();
-            UseStateHandle::set(&min_transport_per_line, new_min_transport_per_line)
+    // A block of cells, copied from a spreadsheet, is set in one update
+    let onpaste_set_transport_rate = {
+        let shape = transport_rate.shape();
+        Callback::from(move |(e, i, j): (onpaste::Event, usize, usize)| {
+            if let Some(edits) = paste_edits(&e, (i, j), shape) {
+                paste_transport_rate.emit(edits)
+            }
         })
     };
 
-    let onchange_set_ships_count_per_type = {
-        reclone!(ships_count_per_type);
-        Callback::from(move |(e, j): (onchange::Event, usize)| {
-            let mut new_ships_count_per_type = ships_count_per_type.deref().to_owned();
-            new_ships_count_per_type[j] = e
-                .target()
-                .unwrap()
-                .unchecked_into::<HtmlInputElement>()
-                .value_as_number() as u16;
-            UseStateHandle::set(&ships_count_per_type, new_ships_count_per_type)
+    let onpaste_set_cost_rate = {
+        let shape = cost_rate.shape();
+        Callback::from(move |(e, i, j): (onpaste::Event, usize, usize)| {
+            if let Some(edits) = paste_edits(&e, (i, j), shape) {
+                paste_cost_rate.emit(edits)
+            }
         })
     };
 
+    let onchange_set_min_transport_per_line =
+        Callback::from(move |(e, i): (onchange::Event, usize)| {
+            set_min_transport_per_line.emit(vec![((i, 0), input_value(&e))])
+        });
+
+    let onchange_set_ships_count_per_type =
+        Callback::from(move |(e, j): (onchange::Event, usize)| {
+            set_ships_count_per_type.emit(vec![(
+                (0, j),
+                e.target()
+                    .unwrap()
+                    .unchecked_into::<HtmlInputElement>()
+                    .value_as_number() as u16,
+            )])
+        });
+
     let onchange_set_available_ship_line = {
         reclone!(available_ship_line);
         Callback::from(move |(e, i, j): (onchange::Event, usize, usize)| {
@@ -125,6 +141,8 @@ pub fn Table(
                                     onsubmit,
                                     onchange_set_transport_rate,
                                     onchange_set_cost_rate,
+                                    onpaste_set_transport_rate,
+                                    onpaste_set_cost_rate,
                                     onchange_set_available_ship_line
                                 );
                                 (1..=n_ships).map(move |ship| { //       j, a_ij
@@ -132,6 +150,8 @@ pub fn Table(
                                         onsubmit,
                                         onchange_set_transport_rate,
                                         onchange_set_cost_rate,
+                                        onpaste_set_transport_rate,
+                                        onpaste_set_cost_rate,
                                         onchange_set_available_ship_line,
                                     );
                                     let onchange_set_transport_rate = Callback::from(move |e: onchange::Event|
@@ -140,9 +160,23 @@ pub fn Table(
                                     let onchange_set_cost_rate = Callback::from(move |e: onchange::Event|
                                         onchange_set_cost_rate.emit((e, line - 1, ship - 1))
                                     );
+                                    let onpaste_set_transport_rate = Callback::from(move |e: onpaste::Event|
+                                        onpaste_set_transport_rate.emit((e, line - 1, ship - 1))
+                                    );
+                                    let onpaste_set_cost_rate = Callback::from(move |e: onpaste::Event|
+                                        onpaste_set_cost_rate.emit((e, line - 1, ship - 1))
+                                    );
                                     let onchange_set_available_ship_line = Callback::from(move |e: onchange::Event|
                                         onchange_set_available_ship_line.emit((e, line - 1, ship - 1))
                                     );
+                                    // Rates of an unavailable ship are not used, so they are not validated
+                                    let is_available = available_ship_line[(line - 1, ship - 1)];
+                                    let transport_rate_error = is_available
+                                        .then(|| validation_error(&transport_rate[(line - 1, ship - 1)], 9999))
+                                        .flatten();
+                                    let cost_rate_error = is_available
+                                        .then(|| validation_error(&cost_rate[(line - 1, ship - 1)], 9999))
+                                        .flatten();
                                     html!{<>
                                         <td
                                             key={format!("{line}_{ship}")}
@@ -151,6 +185,8 @@ pub fn Table(
                                             <input
                                                 key={format!("{line}_{ship}_checkbox")}
                                                 type="checkbox"
+                                                tabindex={tab_index(n_ships, line - 1, 3 * (ship - 1))}
+                                                aria-label={format!("ship available line {line} ship {ship}")}
                                                 checked={available_ship_line[(line - 1, ship - 1)]}
                                                 onchange={onchange_set_available_ship_line}
                                             />
@@ -158,20 +194,30 @@ pub fn Table(
                                                 key={format!("a_{line}_{ship}_input")}
                                                 disabled={!available_ship_line[(line - 1, ship - 1)]}
                                                 type="number"
+                                                tabindex={tab_index(n_ships, line - 1, 3 * (ship - 1) + 1)}
+                                                aria-label={format!("transport rate line {line} ship {ship}")}
+                                                class={classes!(transport_rate_error.is_some().then_some("invalid"))}
+                                                title={transport_rate_error.clone()}
                                                 min=0
                                                 max=9999
-                                                value={transport_rate[(line - 1, ship - 1)].to_string()}
+                                                value={transport_rate[(line - 1, ship - 1)].clone()}
                                                 onchange={onchange_set_transport_rate}
+                                                onpaste={onpaste_set_transport_rate}
                                                 onsubmit={onsubmit.clone()}
                                             />
                                             <input
                                                 key={format!("c_{line}_{ship}_input")}
                                                 disabled={!available_ship_line[(line - 1, ship - 1)]}
                                                 type="number"
+                                                tabindex={tab_index(n_ships, line - 1, 3 * (ship - 1) + 2)}
+                                                aria-label={format!("cost rate line {line} ship {ship}")}
+                                                class={classes!(cost_rate_error.is_some().then_some("invalid"))}
+                                                title={cost_rate_error.clone()}
                                                 min=0
                                                 max=9999
-                                                value={cost_rate[(line-1, ship-1)].to_string()}
+                                                value={cost_rate[(line-1, ship-1)].clone()}
                                                 onchange={onchange_set_cost_rate}
+                                                onpaste={onpaste_set_cost_rate}
                                                 {onsubmit}
                                             />
                                         </td>
@@ -183,13 +229,18 @@ pub fn Table(
                                 let onchange = Callback::from(move |e: onchange::Event|
                                     onchange_set_min_transport_per_line.emit((e, line-1))
                                 );
+                                let error = validation_error(&min_transport_per_line[line - 1], 99999);
                                 html! {<td class={classes!("tg-c3ow")}> // a_i
                                     <input
                                         key={format!("a_{line}_input")}
                                         type="number"
+                                        tabindex={tab_index(n_ships, line - 1, 3 * n_ships)}
+                                        aria-label={format!("min transport line {line}")}
+                                        class={classes!(error.is_some().then_some("invalid"))}
+                                        title={error.clone()}
                                         min=0
                                         max=99999
-                                        value={min_transport_per_line[line-1].to_string()}
+                                        value={min_transport_per_line[line-1].clone()}
                                         {onchange}
                                         {onsubmit}
                                     />
@@ -214,6 +265,8 @@ pub fn Table(
                             <input
                                 key={format!("n_{ship}_input")}
                                 type="number"
+                                tabindex={tab_index(n_ships, n_lines, ship - 1)}
+                                aria-label={format!("ships count ship {ship}")}
                                 min=0
                                 max=9999
                                 value={ships_count_per_type[ship-1].to_string()}
@@ -229,3 +282,258 @@ pub fn Table(
         </tbody>
     </table></div>}
 }
+
+/// Delay after the last edit, before the pending edits are set to the state
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Edits of the cells, that are set to the state together, once no other edit follows them for [`DEBOUNCE`]
+#[derive(Debug)]
+struct PendingEdits<T> {
+    cells: Vec<((usize, usize), T)>,
+    /// Number of the last batch of edits
+    generation: u64,
+}
+
+impl<T> Default for PendingEdits<T> {
+    fn default() -> Self {
+        Self {
+            cells: Vec::new(),
+            generation: 0,
+        }
+    }
+}
+
+impl<T> PendingEdits<T> {
+    /// Queues the batch of edits and returns its generation, that the commit has to be scheduled with
+    fn push(&mut self, edits: impl IntoIterator<Item = ((usize, usize), T)>) -> u64 {
+        self.cells.extend(edits);
+        self.generation += 1;
+        self.generation
+    }
+
+    /// Applies the queued edits to a copy of the `matrix`,
+    /// unless another batch was pushed after the `generation`, which commits them later
+    fn commit<M>(&mut self, generation: u64, matrix: &M) -> Option<M>
+    where
+        M: Clone + IndexMut<(usize, usize), Output = T>,
+    {
+        if generation != self.generation || self.cells.is_empty() {
+            return None;
+        }
+        let mut matrix = matrix.clone();
+        for (cell, value) in self.cells.drain(..) {
+            matrix[cell] = value;
+        }
+        Some(matrix)
+    }
+}
+
+/// Callback, that takes the new values of the cells
+type EditsSetter<T> = Callback<Vec<((usize, usize), T)>>;
+
+/// Callbacks, that set the edits of the `state`. The first one sets them at once, together with the queued ones,
+/// while the second one queues them and sets after [`DEBOUNCE`]
+fn edit_setters<M, T>(
+    state: UseStateHandle<M>,
+    pending: Rc<RefCell<PendingEdits<T>>>,
+) -> (EditsSetter<T>, EditsSetter<T>)
+where
+    M: Clone + IndexMut<(usize, usize), Output = T> + 'static,
+    T: 'static,
+{
+    let set = {
+        reclone!(state, pending);
+        Callback::from(move |edits| {
+            let generation = pending.borrow_mut().push(edits);
+            let new_state = pending.borrow_mut().commit(generation, state.deref());
+            if let Some(new_state) = new_state {
+                UseStateHandle::set(&state, new_state)
+            }
+        })
+    };
+    let set_debounced = Callback::from(move |edits| {
+        let generation = pending.borrow_mut().push(edits);
+        reclone!(state, pending);
+        spawn_local(async move {
+            sleep(DEBOUNCE).await;
+            let new_state = pending.borrow_mut().commit(generation, state.deref());
+            if let Some(new_state) = new_state {
+                UseStateHandle::set(&state, new_state)
+            }
+        })
+    });
+    (set, set_debounced)
+}
+
+fn input_value(e: &onchange::Event) -> String {
+    e.target()
+        .unwrap()
+        .unchecked_into::<HtmlInputElement>()
+        .value()
+}
+
+/// Edits of the block of cells, pasted into the `cell`, if the clipboard holds several of them.
+/// A single value is pasted by the input itself
+fn paste_edits(
+    e: &onpaste::Event,
+    cell: (usize, usize),
+    shape: (usize, usize),
+) -> Option<Vec<((usize, usize), String)>> {
+    let text = e
+        .dyn_ref::<ClipboardEvent>()?
+        .clipboard_data()?
+        .get_data("text")
+        .ok()?;
+    if !text.trim().contains(['\t', '\n']) {
+        return None;
+    }
+    e.prevent_default();
+    Some(pasted_cells(&text, cell, shape))
+}
+
+/// Cells of the tab separated `text`, pasted into the `cell` of a matrix of the `shape`.
+/// The values, that don't fit into the matrix, are dropped
+fn pasted_cells(
+    text: &str,
+    (i, j): (usize, usize),
+    (nrows, ncols): (usize, usize),
+) -> Vec<((usize, usize), String)> {
+    text.lines()
+        .zip(i..nrows)
+        .flat_map(|(line, i)| {
+            line.split('\t')
+                .zip(j..ncols)
+                .map(move |(value, j)| ((i, j), value.trim().to_owned()))
+        })
+        .collect()
+}
+
+/// Message for a typed value, that the solver can't accept, shown on the highlighted input
+fn validation_error(value: &str, max: u32) -> Option<String> {
+    match BigRationalExt::from_decimal_str(value) {
+        Ok(RatioExt::Finite(ratio))
+            if !ratio.is_negative() && ratio <= BigRational::from_integer(max.into()) =>
+        {
+            None
+        }
+        Ok(RatioExt::Finite(_)) => Some(format!("Значення має бути від 0 до {max}")),
+        _ => Some("Некоректне число".to_owned()),
+    }
+}
+
+/// Position of an input in the row-major tab order of the table.
+///
+/// Each line holds the checkbox and both rates of every ship, followed by the minimal transport.
+/// The ships counts are the last line. Disabled rates are skipped by the browser
+fn tab_index(n_ships: usize, line: usize, input: usize) -> String {
+    (1 + line * (3 * n_ships + 1) + input).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_values_are_reported() {
+        assert_eq!(validation_error("12.5", 9999), None);
+        assert_eq!(validation_error("9999", 9999), None);
+        assert_eq!(
+            validation_error("12000", 9999),
+            Some("Значення має бути від 0 до 9999".to_owned())
+        );
+        assert_eq!(
+            validation_error("-1", 9999),
+            Some("Значення має бути від 0 до 9999".to_owned())
+        );
+        assert_eq!(
+            validation_error("", 9999),
+            Some("Некоректне число".to_owned())
+        );
+        assert_eq!(
+            validation_error("Inf", 9999),
+            Some("Некоректне число".to_owned())
+        );
+    }
+
+    #[test]
+    fn rapid_edits_are_set_in_one_update() {
+        let matrix = DMatrix::from_element(2, 2, "0".to_owned());
+        let mut pending = PendingEdits::default();
+        let generations = [
+            pending.push([((0, 0), "1".to_owned())]),
+            pending.push([((0, 0), "12".to_owned())]),
+            pending.push([((1, 1), "5".to_owned())]),
+        ];
+
+        // Every edit schedules a commit, but only the last one sets the state
+        let updates = generations
+            .into_iter()
+            .filter_map(|generation| pending.commit(generation, &matrix))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            updates,
+            [DMatrix::from_row_slice(
+                2,
+                2,
+                &["12", "0", "0", "5"].map(str::to_owned)
+            )]
+        );
+        assert_eq!(pending.commit(3, &matrix), None);
+    }
+
+    #[test]
+    fn committed_edit_sets_the_queued_ones() {
+        let matrix = DMatrix::from_element(2, 2, "0".to_owned());
+        let mut pending = PendingEdits::default();
+        let pasted = pending.push([((0, 0), "1".to_owned()), ((0, 1), "2".to_owned())]);
+        let changed = pending.push([((1, 1), "5".to_owned())]);
+
+        assert_eq!(
+            pending.commit(changed, &matrix),
+            Some(DMatrix::from_row_slice(
+                2,
+                2,
+                &["1", "2", "0", "5"].map(str::to_owned)
+            ))
+        );
+        // The scheduled commit of the pasted block has nothing left to set
+        assert_eq!(pending.commit(pasted, &matrix), None);
+    }
+
+    #[test]
+    fn pasted_block_is_clipped_to_the_matrix() {
+        assert_eq!(
+            pasted_cells("1\t2\t3\n4\t5\t6\n7\t8\t9\n", (2, 1), (4, 3)),
+            [
+                ((2, 1), "1".to_owned()),
+                ((2, 2), "2".to_owned()),
+                ((3, 1), "4".to_owned()),
+                ((3, 2), "5".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tab_index_is_row_major() {
+        let (n_lines, n_ships) = (4, 3);
+        let mut rendered = Vec::new();
+        for line in 0..n_lines {
+            for ship in 0..n_ships {
+                for input in 0..3 {
+                    rendered.push(tab_index(n_ships, line, 3 * ship + input));
+                }
+            }
+            rendered.push(tab_index(n_ships, line, 3 * n_ships));
+        }
+        for ship in 0..n_ships {
+            rendered.push(tab_index(n_ships, n_lines, ship));
+        }
+
+        assert_eq!(
+            rendered,
+            (1..=n_lines * (3 * n_ships + 1) + n_ships)
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+}
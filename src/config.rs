@@ -0,0 +1,199 @@
+use nalgebra::{DMatrix, DVector, RowDVector};
+use serde::Deserialize;
+
+use crate::command::FleetUsage;
+
+/// Inputs of the [`App`](crate::App), that are imported from a `.json` file, like
+///
+/// ```json
+/// {
+///     "transportRate": [[15, 30], [10, 25]],
+///     "costRate": [[15, 70], [20, "23.5"]],
+///     "minTransportPerLine": [300, 200],
+///     "shipsCountPerType": [50, 20],
+///     "minimize": true
+/// }
+/// ```
+///
+/// The numbers may be written as strings, so that the decimals stay exact.
+/// Without `availableShipLine` every ship serves every line, and without `fleetUsage` every ship is used
+#[derive(Debug, Clone, PartialEq)]
+pub struct Configuration {
+    pub available_ship_line: DMatrix<bool>,
+    pub transport_rate: DMatrix<String>,
+    pub cost_rate: DMatrix<String>,
+    pub min_transport_per_line: DVector<String>,
+    pub ships_count_per_type: RowDVector<u16>,
+    pub minimize: bool,
+    pub fleet_usage: FleetUsage,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigurationFile {
+    available_ship_line: Option<Vec<Vec<bool>>>,
+    transport_rate: Vec<Vec<Number>>,
+    cost_rate: Vec<Vec<Number>>,
+    min_transport_per_line: Vec<Number>,
+    ships_count_per_type: Vec<u16>,
+    #[serde(default = "default_minimize")]
+    minimize: bool,
+    #[serde(default)]
+    fleet_usage: FleetUsage,
+}
+
+fn default_minimize() -> bool {
+    true
+}
+
+/// Number as it was written in the file
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Number {
+    Text(String),
+    Number(serde_json::Number),
+}
+
+impl From<Number> for String {
+    fn from(number: Number) -> Self {
+        match number {
+            Number::Text(text) => text,
+            Number::Number(number) => number.to_string(),
+        }
+    }
+}
+
+/// Parses the contents of the imported file.
+///
+/// Fails with the message for the user on the malformed JSON, on the ragged rows,
+/// and on the inputs, which sizes don't match each other
+pub fn parse_configuration(json: &str) -> Result<Configuration, String> {
+    let ConfigurationFile {
+        available_ship_line,
+        transport_rate,
+        cost_rate,
+        min_transport_per_line,
+        ships_count_per_type,
+        minimize,
+        fleet_usage,
+    } = serde_json::from_str(json).map_err(|err| format!("Некоректний файл: {err}"))?;
+
+    let transport_rate = matrix(transport_rate, "обсягів перевезень")?;
+    let cost_rate = matrix(cost_rate, "вартостей перевезень")?;
+    let min_transport_per_line = DVector::from_iterator(
+        min_transport_per_line.len(),
+        min_transport_per_line.into_iter().map(String::from),
+    );
+    let ships_count_per_type = RowDVector::from_vec(ships_count_per_type);
+    let available_ship_line = match available_ship_line {
+        Some(available_ship_line) => matrix(available_ship_line, "доступності суден")?,
+        None => DMatrix::from_element(transport_rate.nrows(), transport_rate.ncols(), true),
+    };
+
+    let mut errors = crate::app::shape_errors(
+        transport_rate.shape(),
+        cost_rate.shape(),
+        min_transport_per_line.nrows(),
+        ships_count_per_type.ncols(),
+    );
+    if available_ship_line.shape() != transport_rate.shape() {
+        errors.push(format!(
+            "Розміри матриць доступності суден {:?} та обсягів перевезень {:?} не збігаються",
+            available_ship_line.shape(),
+            transport_rate.shape()
+        ));
+    }
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+
+    Ok(Configuration {
+        available_ship_line,
+        transport_rate,
+        cost_rate,
+        min_transport_per_line,
+        ships_count_per_type,
+        minimize,
+        fleet_usage,
+    })
+}
+
+/// Matrix of the `rows`, that have to be of the same length
+fn matrix<T, U>(rows: Vec<Vec<T>>, name: &str) -> Result<DMatrix<U>, String>
+where
+    U: From<T> + nalgebra::Scalar,
+{
+    let ncols = rows.first().map_or(0, Vec::len);
+    if let Some(i) = rows.iter().position(|row| row.len() != ncols) {
+        return Err(format!(
+            "Рядок {} матриці {name} має {} елементів замість {ncols}",
+            i + 1,
+            rows[i].len()
+        ));
+    }
+    let nrows = rows.len();
+    // The rows are the columns of the transposed matrix
+    Ok(DMatrix::from_iterator(ncols, nrows, rows.into_iter().flatten().map(U::from)).transpose())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imported_file_is_parsed() {
+        let configuration = parse_configuration(
+            r#"{
+                "transportRate": [[15, 30], [10, 25]],
+                "costRate": [[15, 70], [20, "23.5"]],
+                "minTransportPerLine": [300, 0.1],
+                "shipsCountPerType": [50, 20],
+                "minimize": false,
+                "fleetUsage": "atMost"
+            }"#,
+        )
+        .unwrap();
+        let strings = |strings: &[&str]| strings.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            configuration,
+            Configuration {
+                available_ship_line: DMatrix::from_element(2, 2, true),
+                transport_rate: DMatrix::from_row_slice(2, 2, &strings(&["15", "30", "10", "25"])),
+                cost_rate: DMatrix::from_row_slice(2, 2, &strings(&["15", "70", "20", "23.5"])),
+                min_transport_per_line: DVector::from_vec(strings(&["300", "0.1"])),
+                ships_count_per_type: RowDVector::from_row_slice(&[50, 20]),
+                minimize: false,
+                fleet_usage: FleetUsage::AtMost,
+            }
+        );
+    }
+
+    #[test]
+    fn malformed_files_are_rejected() {
+        assert!(parse_configuration("{")
+            .unwrap_err()
+            .starts_with("Некоректний файл"));
+        assert_eq!(
+            parse_configuration(
+                r#"{
+                    "transportRate": [[15, 30], [10]],
+                    "costRate": [[15, 70], [20, 23]],
+                    "minTransportPerLine": [300, 200],
+                    "shipsCountPerType": [50, 20]
+                }"#
+            ),
+            Err("Рядок 2 матриці обсягів перевезень має 1 елементів замість 2".to_owned())
+        );
+        assert_eq!(
+            parse_configuration(
+                r#"{
+                    "transportRate": [[15, 30], [10, 25]],
+                    "costRate": [[15, 70], [20, 23]],
+                    "minTransportPerLine": [300, 200, 100],
+                    "shipsCountPerType": [50, 20]
+                }"#
+            ),
+            Err("Кількість ліній 3 не збігається з кількістю рядків 2".to_owned())
+        );
+    }
+}
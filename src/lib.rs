@@ -1,6 +1,7 @@
 mod app;
 pub mod command;
 pub mod component;
+pub mod config;
 pub mod helpers;
 
 pub use app::App;